@@ -0,0 +1,254 @@
+use std::collections::HashMap;
+use std::hash::Hash;
+
+#[allow(dead_code)]
+#[derive(Debug, Copy, Clone, Eq, PartialEq)]
+pub enum CycleStrategy {
+    /// Remembers every state seen. Finds the cycle in as few steps as possible,
+    /// but needs `Hash` and keeps a copy of each state.
+    Hashing,
+    /// Brent's algorithm. Keeps only two states, and typically needs fewer steps than Floyd's.
+    Brent,
+    /// Floyd's tortoise and hare. Keeps only two states.
+    Floyd,
+}
+
+/// The sequence repeats with `period` once the first `prefix_length` states have passed.
+#[derive(Debug, Copy, Clone, Eq, PartialEq)]
+pub struct Cycle {
+    pub prefix_length: usize,
+    pub period: usize,
+}
+
+impl Cycle {
+    /// For `n >= prefix_length`, the number of steps past the start of the cycle
+    /// at which the sequence is in the same state as after `n` steps.
+    fn offset(self, n: usize) -> usize {
+        (n - self.prefix_length) % self.period
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct CycleDetection<S> {
+    /// The state after `n` steps.
+    pub state: S,
+    /// `None` if `n` steps were reached before any cycle was found.
+    #[allow(dead_code)]
+    pub cycle: Option<Cycle>,
+}
+
+/// Computes the state reached by applying `step` to `initial` `n` times,
+/// skipping over whole repetitions once the sequence of states is found to cycle.
+pub fn iterate_with_cycle_detection<S>(
+    initial: S,
+    mut step: impl FnMut(&S) -> S,
+    n: usize,
+    strategy: CycleStrategy,
+) -> CycleDetection<S>
+where
+    S: Clone + Eq + Hash,
+{
+    match strategy {
+        CycleStrategy::Hashing => hashing(initial, &mut step, n),
+        CycleStrategy::Brent => brent(initial, &mut step, n),
+        CycleStrategy::Floyd => floyd(initial, &mut step, n),
+    }
+}
+
+fn advance<S>(mut state: S, step: &mut impl FnMut(&S) -> S, count: usize) -> S {
+    for _ in 0..count {
+        state = step(&state);
+    }
+
+    state
+}
+
+fn hashing<S>(initial: S, step: &mut impl FnMut(&S) -> S, n: usize) -> CycleDetection<S>
+where
+    S: Clone + Eq + Hash,
+{
+    let mut seen = HashMap::new();
+    let mut state = initial;
+
+    for i in 0..n {
+        if let Some(&first_seen) = seen.get(&state) {
+            let cycle = Cycle {
+                prefix_length: first_seen,
+                period: i - first_seen,
+            };
+            let remaining = (n - i) % cycle.period;
+
+            return CycleDetection {
+                state: advance(state, step, remaining),
+                cycle: Some(cycle),
+            };
+        }
+
+        seen.insert(state.clone(), i);
+        state = step(&state);
+    }
+
+    CycleDetection { state, cycle: None }
+}
+
+/// Having found the period, locates the start of the cycle
+/// by walking two states `period` apart until they meet.
+fn finish<S>(
+    initial: S,
+    step: &mut impl FnMut(&S) -> S,
+    period: usize,
+    n: usize,
+) -> CycleDetection<S>
+where
+    S: Eq,
+{
+    let mut tortoise = initial;
+    let mut hare = advance(step(&tortoise), step, period - 1);
+    let mut prefix_length = 0;
+    while tortoise != hare {
+        tortoise = step(&tortoise);
+        hare = step(&hare);
+        prefix_length += 1;
+    }
+
+    let cycle = Cycle {
+        prefix_length,
+        period,
+    };
+
+    // Both searches stop no later than step `n`, and only once they are on the cycle,
+    // so `n` is never inside the prefix here.
+    CycleDetection {
+        state: advance(tortoise, step, cycle.offset(n)),
+        cycle: Some(cycle),
+    }
+}
+
+fn brent<S>(initial: S, step: &mut impl FnMut(&S) -> S, n: usize) -> CycleDetection<S>
+where
+    S: Clone + Eq,
+{
+    if n == 0 {
+        return CycleDetection {
+            state: initial,
+            cycle: None,
+        };
+    }
+
+    let mut power = 1;
+    let mut period = 1;
+    let mut tortoise = initial.clone();
+    let mut hare = step(&initial);
+    let mut hare_index = 1;
+
+    while tortoise != hare {
+        if hare_index == n {
+            return CycleDetection {
+                state: hare,
+                cycle: None,
+            };
+        }
+
+        if power == period {
+            tortoise = hare.clone();
+            power *= 2;
+            period = 0;
+        }
+
+        hare = step(&hare);
+        hare_index += 1;
+        period += 1;
+    }
+
+    finish(initial, step, period, n)
+}
+
+fn floyd<S>(initial: S, step: &mut impl FnMut(&S) -> S, n: usize) -> CycleDetection<S>
+where
+    S: Clone + Eq,
+{
+    let mut tortoise = initial.clone();
+    let mut hare = initial.clone();
+    let mut tortoise_index = 0;
+
+    loop {
+        if tortoise_index == n {
+            return CycleDetection {
+                state: tortoise,
+                cycle: None,
+            };
+        }
+
+        tortoise = step(&tortoise);
+        tortoise_index += 1;
+        let halfway = step(&hare);
+        hare = step(&halfway);
+
+        if tortoise == hare {
+            break;
+        }
+    }
+
+    // The tortoise is now somewhere on the cycle, so walking the hare round once gives the period.
+    let mut period = 1;
+    hare = step(&tortoise);
+    while tortoise != hare {
+        hare = step(&hare);
+        period += 1;
+    }
+
+    finish(initial, step, period, n)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // 3 -> 10 -> 101 -> 2 -> 5 -> 26 -> 167 -> 95 -> 101 -> ...
+    fn step(&x: &u64) -> u64 {
+        (x * x + 1) % 255
+    }
+
+    #[test]
+    fn test_strategies_agree_with_brute_force() {
+        for n in [0, 1, 2, 5, 8, 9, 100, 1_000_003] {
+            let expected = if n < 1000 {
+                advance(3, &mut step, n)
+            } else {
+                advance(3, &mut step, 2 + (n - 2) % 6)
+            };
+
+            for strategy in [
+                CycleStrategy::Hashing,
+                CycleStrategy::Brent,
+                CycleStrategy::Floyd,
+            ] {
+                let result = iterate_with_cycle_detection(3, step, n, strategy);
+                assert_eq!(expected, result.state, "{strategy:?}, n = {n}");
+
+                if let Some(cycle) = result.cycle {
+                    assert_eq!(
+                        Cycle {
+                            prefix_length: 2,
+                            period: 6
+                        },
+                        cycle,
+                        "{strategy:?}, n = {n}"
+                    );
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn test_cycle_found_for_large_n() {
+        for strategy in [
+            CycleStrategy::Hashing,
+            CycleStrategy::Brent,
+            CycleStrategy::Floyd,
+        ] {
+            let result = iterate_with_cycle_detection(3, step, 1_000_000_000, strategy);
+            assert!(result.cycle.is_some(), "{strategy:?}");
+        }
+    }
+}
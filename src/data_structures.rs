@@ -1,6 +1,30 @@
+pub use bit_grid::BitGrid;
+pub use cuboid::Cuboid;
 pub use direction::Direction;
 pub use grid_2d::Grid2D;
 pub use grid_2d::GridPoint2D;
+pub use grid_3d::{Axis, Grid3D, GridPoint3D};
+#[allow(unused_imports)]
+pub use hyper_rect::{DisjointUnion, HyperRect};
+#[allow(unused_imports)]
+pub use interval_set::{IntervalSet, LinearSegment, PiecewiseLinearMap};
+#[allow(unused_imports)]
+pub use pattern_matcher::{PatternMatch, PatternMatcher};
+#[allow(unused_imports)]
+pub use regions::{Component, ComponentId, Components, Connectivity};
+#[allow(unused_imports)]
+pub use sparse_grid::{BoundingBox, SparseGrid, SparsePoint2D};
+#[allow(unused_imports)]
+pub use sub_grid::{SubGrid, SubGridMut};
 
+mod bit_grid;
+mod cuboid;
 mod direction;
 mod grid_2d;
+mod grid_3d;
+mod hyper_rect;
+mod interval_set;
+mod pattern_matcher;
+mod regions;
+mod sparse_grid;
+mod sub_grid;
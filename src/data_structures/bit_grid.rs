@@ -0,0 +1,308 @@
+use crate::data_structures::{Direction, Grid2D, GridPoint2D};
+use std::fmt::{Display, Formatter};
+use std::ops::{BitAndAssign, BitOrAssign};
+
+const WORD_BITS: usize = u64::BITS as usize;
+
+/// A grid of booleans, packed 64 to a word.
+/// Each row starts on a fresh word, so row-wise operations never need to straddle rows.
+/// Bits past the last column are always kept clear, so equality and hashing are exact.
+#[derive(Debug, Clone, Eq, PartialEq, Hash)]
+pub struct BitGrid {
+    words: Vec<u64>,
+    n_rows: usize,
+    n_cols: usize,
+    words_per_row: usize,
+}
+
+impl BitGrid {
+    pub fn new(n_rows: usize, n_cols: usize) -> Self {
+        let words_per_row = n_cols.div_ceil(WORD_BITS);
+        Self {
+            words: vec![0; n_rows * words_per_row],
+            n_rows,
+            n_cols,
+            words_per_row,
+        }
+    }
+
+    /// Sets the bits for cells of `grid` which satisfy `predicate`.
+    pub fn from_grid<T>(grid: &Grid2D<T>, predicate: impl Fn(&T) -> bool) -> Self {
+        let mut result = Self::new(grid.n_rows(), grid.n_cols());
+        for row in 0..grid.n_rows() {
+            for (col, value) in grid.row_unchecked(row).iter().enumerate() {
+                if predicate(value) {
+                    result.set(GridPoint2D::new(row, col));
+                }
+            }
+        }
+
+        result
+    }
+
+    #[allow(dead_code)]
+    pub fn n_rows(&self) -> usize {
+        self.n_rows
+    }
+
+    #[allow(dead_code)]
+    pub fn n_cols(&self) -> usize {
+        self.n_cols
+    }
+
+    pub fn in_bounds(&self, point: GridPoint2D) -> bool {
+        point.row < self.n_rows && point.col < self.n_cols
+    }
+
+    fn word_and_mask(&self, point: GridPoint2D) -> (usize, u64) {
+        (
+            point.row * self.words_per_row + point.col / WORD_BITS,
+            1 << (point.col % WORD_BITS),
+        )
+    }
+
+    /// Out of bounds points are never set.
+    pub fn test(&self, point: GridPoint2D) -> bool {
+        if !self.in_bounds(point) {
+            return false;
+        }
+
+        let (word, mask) = self.word_and_mask(point);
+        self.words[word] & mask != 0
+    }
+
+    /// Panics if `point` is out of bounds.
+    pub fn set(&mut self, point: GridPoint2D) {
+        assert!(self.in_bounds(point));
+        let (word, mask) = self.word_and_mask(point);
+        self.words[word] |= mask;
+    }
+
+    /// Panics if `point` is out of bounds.
+    #[allow(dead_code)]
+    pub fn clear(&mut self, point: GridPoint2D) {
+        assert!(self.in_bounds(point));
+        let (word, mask) = self.word_and_mask(point);
+        self.words[word] &= !mask;
+    }
+
+    /// Sets `point` and reports whether it was previously clear.
+    /// Panics if `point` is out of bounds.
+    pub fn insert(&mut self, point: GridPoint2D) -> bool {
+        assert!(self.in_bounds(point));
+        let (word, mask) = self.word_and_mask(point);
+        let was_clear = self.words[word] & mask == 0;
+        self.words[word] |= mask;
+        was_clear
+    }
+
+    pub fn clear_all(&mut self) {
+        self.words.fill(0);
+    }
+
+    pub fn count_ones(&self) -> usize {
+        self.words
+            .iter()
+            .map(|word| word.count_ones() as usize)
+            .sum()
+    }
+
+    #[allow(dead_code)]
+    pub fn row_words(&self, row_num: usize) -> &[u64] {
+        let start = row_num * self.words_per_row;
+        &self.words[start..start + self.words_per_row]
+    }
+
+    fn row_words_mut(&mut self, row_num: usize) -> &mut [u64] {
+        let start = row_num * self.words_per_row;
+        &mut self.words[start..start + self.words_per_row]
+    }
+
+    /// Clears any bits past the last column
+    fn mask_trailing(&mut self) {
+        let used_bits = self.n_cols % WORD_BITS;
+        if used_bits == 0 {
+            return;
+        }
+
+        let mask = (1 << used_bits) - 1;
+        for row in 0..self.n_rows {
+            *self.row_words_mut(row).last_mut().unwrap() &= mask;
+        }
+    }
+
+    /// Moves every set cell one step in `direction`. Cells moved off the grid are lost.
+    pub fn shift(&mut self, direction: Direction) {
+        // With no rows, there is no row of words to shift in or out.
+        if self.n_rows == 0 {
+            return;
+        }
+
+        match direction {
+            Direction::Up => {
+                self.words.rotate_left(self.words_per_row);
+                let len = self.words.len();
+                self.words[len - self.words_per_row..].fill(0);
+            }
+            Direction::Down => {
+                self.words.rotate_right(self.words_per_row);
+                self.words[..self.words_per_row].fill(0);
+            }
+            Direction::Left => {
+                for row in 0..self.n_rows {
+                    let words = self.row_words_mut(row);
+                    for i in 0..words.len() {
+                        let carry = words.get(i + 1).map_or(0, |next| next << (WORD_BITS - 1));
+                        words[i] = (words[i] >> 1) | carry;
+                    }
+                }
+            }
+            Direction::Right => {
+                for row in 0..self.n_rows {
+                    let words = self.row_words_mut(row);
+                    for i in (0..words.len()).rev() {
+                        let carry = if i > 0 {
+                            words[i - 1] >> (WORD_BITS - 1)
+                        } else {
+                            0
+                        };
+                        words[i] = (words[i] << 1) | carry;
+                    }
+                }
+                self.mask_trailing();
+            }
+        }
+    }
+
+    /// Returns the set of cells which are exactly one step away from a set cell.
+    /// Set cells are only included if they neighbour another set cell.
+    pub fn neighbours(&self) -> Self {
+        let mut result = Self::new(self.n_rows, self.n_cols);
+        for direction in Direction::ALL {
+            let mut shifted = self.clone();
+            shifted.shift(direction);
+            result |= &shifted;
+        }
+
+        result
+    }
+
+    #[allow(dead_code)]
+    pub fn points(&self) -> impl Iterator<Item = GridPoint2D> + '_ {
+        (0..self.n_rows).flat_map(move |row| {
+            (0..self.n_cols)
+                .map(move |col| GridPoint2D::new(row, col))
+                .filter(|&point| self.test(point))
+        })
+    }
+}
+
+/// Panics if the dimensions differ.
+impl BitAndAssign<&BitGrid> for BitGrid {
+    fn bitand_assign(&mut self, rhs: &BitGrid) {
+        assert_eq!((self.n_rows, self.n_cols), (rhs.n_rows, rhs.n_cols));
+        for (word, other) in self.words.iter_mut().zip(&rhs.words) {
+            *word &= other;
+        }
+    }
+}
+
+/// Panics if the dimensions differ.
+impl BitOrAssign<&BitGrid> for BitGrid {
+    fn bitor_assign(&mut self, rhs: &BitGrid) {
+        assert_eq!((self.n_rows, self.n_cols), (rhs.n_rows, rhs.n_cols));
+        for (word, other) in self.words.iter_mut().zip(&rhs.words) {
+            *word |= other;
+        }
+    }
+}
+
+impl Display for BitGrid {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        for row in 0..self.n_rows {
+            for col in 0..self.n_cols {
+                if self.test(GridPoint2D::new(row, col)) {
+                    write!(f, "#")?;
+                } else {
+                    write!(f, ".")?;
+                }
+            }
+
+            if row < self.n_rows - 1 {
+                writeln!(f)?;
+            }
+        }
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::parser;
+
+    fn bit_grid(input: &str) -> BitGrid {
+        let grid = parser::as_grid2d_by_char_from_str(input, Ok).unwrap();
+        BitGrid::from_grid(&grid, |&c| c == '#')
+    }
+
+    #[test]
+    fn test_set_test_clear() {
+        let mut grid = BitGrid::new(3, 70);
+        grid.set(GridPoint2D::new(1, 65));
+        grid.set(GridPoint2D::new(2, 0));
+        assert!(grid.test(GridPoint2D::new(1, 65)));
+        assert!(!grid.test(GridPoint2D::new(1, 64)));
+        assert!(!grid.test(GridPoint2D::new(5, 5)));
+        assert_eq!(2, grid.count_ones());
+
+        grid.clear(GridPoint2D::new(1, 65));
+        assert_eq!(1, grid.count_ones());
+        assert!(!grid.insert(GridPoint2D::new(2, 0)));
+        assert!(grid.insert(GridPoint2D::new(0, 69)));
+    }
+
+    #[test]
+    fn test_shift_across_words() {
+        let mut grid = BitGrid::new(2, 130);
+        grid.set(GridPoint2D::new(0, 63));
+        grid.set(GridPoint2D::new(1, 129));
+
+        grid.shift(Direction::Right);
+        assert!(grid.test(GridPoint2D::new(0, 64)));
+        assert_eq!(1, grid.count_ones());
+
+        grid.shift(Direction::Left);
+        grid.shift(Direction::Down);
+        assert!(grid.test(GridPoint2D::new(1, 63)));
+        assert_eq!(1, grid.count_ones());
+
+        grid.shift(Direction::Down);
+        assert_eq!(0, grid.count_ones());
+    }
+
+    #[test]
+    fn test_shift_empty() {
+        for (n_rows, n_cols) in [(0, 5), (0, 0), (3, 0)] {
+            let mut grid = BitGrid::new(n_rows, n_cols);
+            for direction in Direction::ALL {
+                grid.shift(direction);
+            }
+            assert_eq!(0, grid.count_ones());
+            assert_eq!(0, grid.neighbours().count_ones());
+        }
+    }
+
+    #[test]
+    fn test_neighbours() {
+        let grid = bit_grid(".....\n.....\n..#..\n.....\n.....");
+        let expected = bit_grid(".....\n..#..\n.#.#.\n..#..\n.....");
+        assert_eq!(expected, grid.neighbours());
+
+        let mut frontier = grid.neighbours();
+        frontier |= &grid;
+        frontier &= &bit_grid("#####\n###.#\n#.#.#\n#####\n#####");
+        assert_eq!(".....\n..#..\n..#..\n..#..\n.....", frontier.to_string());
+    }
+}
@@ -0,0 +1,148 @@
+use crate::data_structures::{GridPoint2D, GridPoint3D};
+use std::cmp::{max, min};
+use std::fmt::{Display, Formatter};
+
+/// Axis-aligned box of grid cells, inclusive on both ends.
+#[derive(Debug, Copy, Clone, Hash, Eq, PartialEq)]
+pub struct Cuboid {
+    min: GridPoint3D,
+    max: GridPoint3D,
+}
+
+impl Cuboid {
+    /// The corners may be given in any order.
+    pub fn new(a: GridPoint3D, b: GridPoint3D) -> Self {
+        Self {
+            min: GridPoint3D::new(min(a.x, b.x), min(a.y, b.y), min(a.z, b.z)),
+            max: GridPoint3D::new(max(a.x, b.x), max(a.y, b.y), max(a.z, b.z)),
+        }
+    }
+
+    pub fn min(self) -> GridPoint3D {
+        self.min
+    }
+
+    pub fn max(self) -> GridPoint3D {
+        self.max
+    }
+
+    #[allow(dead_code)]
+    pub fn volume(self) -> usize {
+        (self.max.x + 1 - self.min.x)
+            * (self.max.y + 1 - self.min.y)
+            * (self.max.z + 1 - self.min.z)
+    }
+
+    #[allow(dead_code)]
+    pub fn contains(self, point: GridPoint3D) -> bool {
+        (self.min.x..=self.max.x).contains(&point.x)
+            && (self.min.y..=self.max.y).contains(&point.y)
+            && (self.min.z..=self.max.z).contains(&point.z)
+    }
+
+    /// Returns `None` if the cuboids do not overlap.
+    #[allow(dead_code)]
+    pub fn intersection(self, other: Self) -> Option<Self> {
+        let low = GridPoint3D::new(
+            max(self.min.x, other.min.x),
+            max(self.min.y, other.min.y),
+            max(self.min.z, other.min.z),
+        );
+        let high = GridPoint3D::new(
+            min(self.max.x, other.max.x),
+            min(self.max.y, other.max.y),
+            min(self.max.z, other.max.z),
+        );
+
+        if low.x > high.x || low.y > high.y || low.z > high.z {
+            None
+        } else {
+            Some(Self {
+                min: low,
+                max: high,
+            })
+        }
+    }
+
+    #[allow(dead_code)]
+    pub fn intersects(self, other: Self) -> bool {
+        self.intersection(other).is_some()
+    }
+
+    /// Returns `None` if the translation would move any coordinate below zero.
+    pub fn translate(self, dx: isize, dy: isize, dz: isize) -> Option<Self> {
+        fn shift(point: GridPoint3D, dx: isize, dy: isize, dz: isize) -> Option<GridPoint3D> {
+            Some(GridPoint3D::new(
+                point.x.checked_add_signed(dx)?,
+                point.y.checked_add_signed(dy)?,
+                point.z.checked_add_signed(dz)?,
+            ))
+        }
+
+        Some(Self {
+            min: shift(self.min, dx, dy, dz)?,
+            max: shift(self.max, dx, dy, dz)?,
+        })
+    }
+
+    /// Projection onto the xy-plane, with `x` as the row and `y` as the column.
+    pub fn footprint(self) -> impl Iterator<Item = GridPoint2D> {
+        (self.min.x..=self.max.x)
+            .flat_map(move |x| (self.min.y..=self.max.y).map(move |y| GridPoint2D::new(x, y)))
+    }
+
+    pub fn points(self) -> impl Iterator<Item = GridPoint3D> {
+        (self.min.z..=self.max.z).flat_map(move |z| {
+            self.footprint()
+                .map(move |point| GridPoint3D::new(point.row, point.col, z))
+        })
+    }
+}
+
+impl Display for Cuboid {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}~{}", self.min, self.max)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_intersection() {
+        let a = Cuboid::new(GridPoint3D::new(0, 0, 0), GridPoint3D::new(3, 3, 3));
+        let b = Cuboid::new(GridPoint3D::new(5, 2, 1), GridPoint3D::new(2, 6, 2));
+
+        assert_eq!(
+            Some(Cuboid::new(
+                GridPoint3D::new(2, 2, 1),
+                GridPoint3D::new(3, 3, 2)
+            )),
+            a.intersection(b)
+        );
+
+        let c = Cuboid::new(GridPoint3D::new(4, 0, 0), GridPoint3D::new(4, 3, 3));
+        assert_eq!(None, a.intersection(c));
+    }
+
+    #[test]
+    fn test_translate_and_footprint() {
+        let brick = Cuboid::new(GridPoint3D::new(1, 0, 5), GridPoint3D::new(1, 2, 5));
+
+        assert_eq!(None, brick.translate(0, -1, 0));
+        let dropped = brick.translate(0, 0, -4).unwrap();
+        assert_eq!(GridPoint3D::new(1, 0, 1), dropped.min());
+        assert_eq!(3, dropped.volume());
+
+        let footprint: Vec<_> = dropped.footprint().collect();
+        assert_eq!(
+            vec![
+                GridPoint2D::new(1, 0),
+                GridPoint2D::new(1, 1),
+                GridPoint2D::new(1, 2)
+            ],
+            footprint
+        );
+    }
+}
@@ -208,6 +208,28 @@ impl<T> Grid2D<T> {
         }
     }
 
+    pub fn column_mut_unchecked(
+        &mut self,
+        column_num: usize,
+    ) -> impl DoubleEndedIterator<Item = &mut T> {
+        self.vec
+            .iter_mut()
+            .skip(column_num)
+            .step_by(self.n_cols.max(1))
+    }
+
+    #[allow(dead_code)]
+    pub fn column_mut(
+        &mut self,
+        column_num: usize,
+    ) -> Option<impl DoubleEndedIterator<Item = &mut T>> {
+        if column_num < self.n_cols {
+            Some(self.column_mut_unchecked(column_num))
+        } else {
+            None
+        }
+    }
+
     #[allow(dead_code)]
     pub fn rows(&self) -> RowIterator<T> {
         RowIterator::new(self)
@@ -223,6 +245,33 @@ impl<T> Grid2D<T> {
     pub fn cells(&self) -> impl Iterator<Item = &T> {
         self.vec.iter()
     }
+
+    /// Consumes the grid, yielding cells in row-major order.
+    pub fn into_cells(self) -> impl Iterator<Item = T> {
+        self.vec.into_iter()
+    }
+}
+
+impl<T> Grid2D<T>
+where
+    T: PartialEq,
+{
+    /// Number of columns at which the two rows hold different values.
+    pub fn row_differences(&self, row_a: usize, row_b: usize) -> usize {
+        self.row_unchecked(row_a)
+            .iter()
+            .zip(self.row_unchecked(row_b))
+            .filter(|(a, b)| a != b)
+            .count()
+    }
+
+    /// Number of rows at which the two columns hold different values.
+    pub fn column_differences(&self, column_a: usize, column_b: usize) -> usize {
+        self.column_unchecked(column_a)
+            .zip(self.column_unchecked(column_b))
+            .filter(|(a, b)| a != b)
+            .count()
+    }
 }
 
 impl<T> Grid2D<T>
@@ -0,0 +1,235 @@
+use crate::data_structures::{Grid2D, GridPoint2D};
+use std::cmp::max;
+use std::fmt::{Display, Formatter};
+use std::ops::{Add, AddAssign};
+
+#[derive(Debug, Default, Copy, Clone, Hash, Eq, PartialEq, Ord, PartialOrd)]
+pub struct GridPoint3D {
+    pub x: usize,
+    pub y: usize,
+    pub z: usize,
+}
+
+impl GridPoint3D {
+    pub fn new(x: usize, y: usize, z: usize) -> Self {
+        Self { x, y, z }
+    }
+
+    /// Component-wise maximum
+    pub fn max_components(self, other: Self) -> Self {
+        Self::new(
+            max(self.x, other.x),
+            max(self.y, other.y),
+            max(self.z, other.z),
+        )
+    }
+
+    pub fn coordinate(self, axis: Axis) -> usize {
+        match axis {
+            Axis::X => self.x,
+            Axis::Y => self.y,
+            Axis::Z => self.z,
+        }
+    }
+
+    /// Projects onto the xy-plane, with `x` as the row and `y` as the column.
+    #[allow(dead_code)]
+    pub fn discard_z(self) -> GridPoint2D {
+        GridPoint2D::new(self.x, self.y)
+    }
+}
+
+impl Add for GridPoint3D {
+    type Output = GridPoint3D;
+
+    fn add(self, rhs: Self) -> Self::Output {
+        Self::new(self.x + rhs.x, self.y + rhs.y, self.z + rhs.z)
+    }
+}
+
+impl AddAssign for GridPoint3D {
+    fn add_assign(&mut self, rhs: Self) {
+        *self = self.add(rhs)
+    }
+}
+
+impl Display for GridPoint3D {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(f, "({}, {}, {})", self.x, self.y, self.z)
+    }
+}
+
+#[derive(Debug, Copy, Clone, Eq, PartialEq, Hash)]
+pub enum Axis {
+    X,
+    Y,
+    Z,
+}
+
+impl Axis {
+    pub const ALL: [Self; 3] = [Self::X, Self::Y, Self::Z];
+}
+
+/// Cells are stored with `x` varying fastest and `z` slowest,
+/// so each z-layer is contiguous.
+#[derive(Debug, Clone, Eq, PartialEq, Hash)]
+pub struct Grid3D<T> {
+    vec: Vec<T>,
+    n_x: usize,
+    n_y: usize,
+    n_z: usize,
+}
+
+impl<T> Grid3D<T>
+where
+    T: Clone,
+{
+    pub fn new(n_x: usize, n_y: usize, n_z: usize, init: T) -> Self {
+        Self {
+            vec: vec![init; n_x * n_y * n_z],
+            n_x,
+            n_y,
+            n_z,
+        }
+    }
+
+    /// Creates a 2D grid from the cells where `axis` has the given coordinate.
+    /// The remaining axes keep their order: a z-slice has x as rows and y as columns,
+    /// a y-slice has x and z, and an x-slice has y and z.
+    #[allow(dead_code)]
+    pub fn slice(&self, axis: Axis, index: usize) -> Option<Grid2D<T>> {
+        let (n_rows, n_cols) = match axis {
+            Axis::X => (self.n_y, self.n_z),
+            Axis::Y => (self.n_x, self.n_z),
+            Axis::Z => (self.n_x, self.n_y),
+        };
+
+        if index >= self.dimension(axis) {
+            return None;
+        }
+
+        let mut vec = Vec::with_capacity(n_rows * n_cols);
+        for row in 0..n_rows {
+            for col in 0..n_cols {
+                let point = match axis {
+                    Axis::X => GridPoint3D::new(index, row, col),
+                    Axis::Y => GridPoint3D::new(row, index, col),
+                    Axis::Z => GridPoint3D::new(row, col, index),
+                };
+                vec.push(self.get_unchecked(point).clone());
+            }
+        }
+
+        Some(Grid2D::from(vec, n_rows, n_cols))
+    }
+}
+
+impl<T> Grid3D<T> {
+    #[allow(dead_code)]
+    pub fn from(vec: Vec<T>, n_x: usize, n_y: usize, n_z: usize) -> Self {
+        assert_eq!(vec.len(), n_x * n_y * n_z);
+
+        Self { vec, n_x, n_y, n_z }
+    }
+
+    #[allow(dead_code)]
+    pub fn n_x(&self) -> usize {
+        self.n_x
+    }
+
+    #[allow(dead_code)]
+    pub fn n_y(&self) -> usize {
+        self.n_y
+    }
+
+    #[allow(dead_code)]
+    pub fn n_z(&self) -> usize {
+        self.n_z
+    }
+
+    pub fn dimension(&self, axis: Axis) -> usize {
+        match axis {
+            Axis::X => self.n_x,
+            Axis::Y => self.n_y,
+            Axis::Z => self.n_z,
+        }
+    }
+
+    #[allow(dead_code)]
+    pub fn len(&self) -> usize {
+        self.vec.len()
+    }
+
+    fn index(&self, point: GridPoint3D) -> usize {
+        (point.z * self.n_y + point.y) * self.n_x + point.x
+    }
+
+    pub fn in_bounds(&self, point: GridPoint3D) -> bool {
+        point.x < self.n_x && point.y < self.n_y && point.z < self.n_z
+    }
+
+    pub fn get_unchecked(&self, point: GridPoint3D) -> &T {
+        &self.vec[self.index(point)]
+    }
+
+    pub fn get_mut_unchecked(&mut self, point: GridPoint3D) -> &mut T {
+        let index = self.index(point);
+        &mut self.vec[index]
+    }
+
+    #[allow(dead_code)]
+    pub fn get(&self, point: GridPoint3D) -> Option<&T> {
+        if self.in_bounds(point) {
+            Some(self.get_unchecked(point))
+        } else {
+            None
+        }
+    }
+
+    #[allow(dead_code)]
+    pub fn get_mut(&mut self, point: GridPoint3D) -> Option<&mut T> {
+        if self.in_bounds(point) {
+            Some(self.get_mut_unchecked(point))
+        } else {
+            None
+        }
+    }
+
+    #[allow(dead_code)]
+    pub fn cells(&self) -> impl Iterator<Item = &T> {
+        self.vec.iter()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_slices() {
+        // x varies fastest, so this is value = 100x + 10y + z
+        let mut grid = Grid3D::new(2, 3, 4, 0);
+        for x in 0..2 {
+            for y in 0..3 {
+                for z in 0..4 {
+                    *grid.get_mut(GridPoint3D::new(x, y, z)).unwrap() = 100 * x + 10 * y + z;
+                }
+            }
+        }
+
+        let z_slice = grid.slice(Axis::Z, 3).unwrap();
+        assert_eq!((2, 3), (z_slice.n_rows(), z_slice.n_cols()));
+        assert_eq!(Some(&113), z_slice.get(GridPoint2D::new(1, 1)));
+
+        let y_slice = grid.slice(Axis::Y, 2).unwrap();
+        assert_eq!((2, 4), (y_slice.n_rows(), y_slice.n_cols()));
+        assert_eq!(Some(&121), y_slice.get(GridPoint2D::new(1, 1)));
+
+        let x_slice = grid.slice(Axis::X, 0).unwrap();
+        assert_eq!((3, 4), (x_slice.n_rows(), x_slice.n_cols()));
+        assert_eq!(Some(&23), x_slice.get(GridPoint2D::new(2, 3)));
+
+        assert_eq!(None, grid.slice(Axis::X, 2));
+        assert_eq!(None, grid.get(GridPoint3D::new(0, 3, 0)));
+    }
+}
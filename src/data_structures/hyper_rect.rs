@@ -0,0 +1,282 @@
+use std::fmt::{Display, Formatter};
+use std::ops::Range;
+
+/// Axis-aligned box in any number of dimensions, half-open on every axis.
+/// Always contains at least one point.
+#[derive(Debug, Clone, Hash, Eq, PartialEq)]
+pub struct HyperRect {
+    start: Vec<i64>,
+    end: Vec<i64>,
+}
+
+#[allow(dead_code)]
+impl HyperRect {
+    /// Returns `None` if the box would be empty along any axis,
+    /// or `start` and `end` have different numbers of axes.
+    pub fn new(start: Vec<i64>, end: Vec<i64>) -> Option<Self> {
+        (start.len() == end.len() && start.iter().zip(&end).all(|(s, e)| s < e))
+            .then_some(Self { start, end })
+    }
+
+    /// Returns `None` if any range is empty.
+    pub fn from_ranges(ranges: impl IntoIterator<Item = Range<i64>>) -> Option<Self> {
+        let (start, end) = ranges.into_iter().map(|r| (r.start, r.end)).unzip();
+        Self::new(start, end)
+    }
+
+    pub fn dimensions(&self) -> usize {
+        self.start.len()
+    }
+
+    pub fn start(&self) -> &[i64] {
+        &self.start
+    }
+
+    /// Exclusive on every axis
+    pub fn end(&self) -> &[i64] {
+        &self.end
+    }
+
+    pub fn range(&self, axis: usize) -> Range<i64> {
+        self.start[axis]..self.end[axis]
+    }
+
+    pub fn side_length(&self, axis: usize) -> u64 {
+        self.start[axis].abs_diff(self.end[axis])
+    }
+
+    /// The number of integer points in the box.
+    pub fn volume(&self) -> u128 {
+        self.checked_volume().expect("Overflow computing volume")
+    }
+
+    /// As `volume`, but `None` if it does not fit in a `u128`.
+    pub fn checked_volume(&self) -> Option<u128> {
+        (0..self.dimensions()).try_fold(1_u128, |volume, axis| {
+            volume.checked_mul(self.side_length(axis) as u128)
+        })
+    }
+
+    /// Points with a different number of axes are never contained.
+    pub fn contains(&self, point: &[i64]) -> bool {
+        point.len() == self.dimensions()
+            && (0..self.dimensions()).all(|axis| self.range(axis).contains(&point[axis]))
+    }
+
+    /// Returns `None` if the boxes do not overlap.
+    /// Boxes with different numbers of axes never overlap.
+    pub fn intersection(&self, other: &Self) -> Option<Self> {
+        if self.dimensions() != other.dimensions() {
+            return None;
+        }
+
+        let mut start = self.start.clone();
+        let mut end = self.end.clone();
+        for axis in 0..self.dimensions() {
+            start[axis] = start[axis].max(other.start[axis]);
+            end[axis] = end[axis].min(other.end[axis]);
+        }
+
+        Self::new(start, end)
+    }
+
+    pub fn intersects(&self, other: &Self) -> bool {
+        self.intersection(other).is_some()
+    }
+
+    /// Splits the box by the hyperplane `axis = at`,
+    /// into the parts below it and the parts at or above it.
+    /// Either side is `None` if the plane does not cut through the box.
+    pub fn split(&self, axis: usize, at: i64) -> (Option<Self>, Option<Self>) {
+        if at <= self.start[axis] {
+            return (None, Some(self.clone()));
+        }
+        if at >= self.end[axis] {
+            return (Some(self.clone()), None);
+        }
+
+        let mut below = self.clone();
+        below.end[axis] = at;
+        let mut above = self.clone();
+        above.start[axis] = at;
+
+        (Some(below), Some(above))
+    }
+
+    /// Disjoint boxes covering the points of `self` which are not in `other`.
+    /// At most two boxes are produced per axis.
+    pub fn difference(&self, other: &Self) -> Vec<Self> {
+        if !self.intersects(other) {
+            return vec![self.clone()];
+        }
+
+        let mut pieces = vec![];
+        let mut remaining = self.clone();
+        for axis in 0..self.dimensions() {
+            // Peel off the slabs either side of `other` along this axis.
+            // What is left lies within `other` on every axis so far.
+            let (below, rest) = remaining.split(axis, other.start[axis]);
+            pieces.extend(below);
+            let Some(rest) = rest else {
+                return pieces;
+            };
+
+            let (rest, above) = rest.split(axis, other.end[axis]);
+            pieces.extend(above);
+            let Some(rest) = rest else {
+                return pieces;
+            };
+
+            remaining = rest;
+        }
+
+        pieces
+    }
+}
+
+/// e.g. `[1, 3) x [0, 5)`
+impl Display for HyperRect {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        for axis in 0..self.dimensions() {
+            if axis > 0 {
+                write!(f, " x ")?;
+            }
+            write!(f, "[{}, {})", self.start[axis], self.end[axis])?;
+        }
+
+        Ok(())
+    }
+}
+
+/// A union of boxes, stored as pairwise disjoint pieces
+/// so that the volume of overlapping boxes is only counted once.
+#[derive(Debug, Clone, Default, Eq, PartialEq)]
+pub struct DisjointUnion {
+    rects: Vec<HyperRect>,
+}
+
+#[allow(dead_code)]
+impl DisjointUnion {
+    pub fn new() -> Self {
+        Self { rects: vec![] }
+    }
+
+    /// The disjoint pieces making up the union. Their order is unspecified.
+    pub fn rects(&self) -> &[HyperRect] {
+        &self.rects
+    }
+
+    /// Adds the parts of `rect` not already covered.
+    pub fn insert(&mut self, rect: HyperRect) {
+        let mut new_pieces = vec![rect];
+        for existing in &self.rects {
+            new_pieces = new_pieces
+                .iter()
+                .flat_map(|piece| piece.difference(existing))
+                .collect();
+            if new_pieces.is_empty() {
+                return;
+            }
+        }
+
+        self.rects.extend(new_pieces);
+    }
+
+    pub fn volume(&self) -> u128 {
+        self.rects.iter().map(HyperRect::volume).sum()
+    }
+
+    pub fn contains(&self, point: &[i64]) -> bool {
+        self.rects.iter().any(|rect| rect.contains(point))
+    }
+}
+
+impl FromIterator<HyperRect> for DisjointUnion {
+    fn from_iter<I: IntoIterator<Item = HyperRect>>(iter: I) -> Self {
+        let mut union = Self::new();
+        for rect in iter {
+            union.insert(rect);
+        }
+
+        union
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn rect<const N: usize>(ranges: [Range<i64>; N]) -> HyperRect {
+        HyperRect::from_ranges(ranges).unwrap()
+    }
+
+    #[test]
+    fn test_split_and_intersect() {
+        let a = rect([0..10, 0..4]);
+        assert_eq!(40, a.volume());
+        assert_eq!(None, HyperRect::from_ranges([0..10, 3..3]));
+
+        assert_eq!(
+            (Some(rect([0..10, 0..1])), Some(rect([0..10, 1..4]))),
+            a.split(1, 1)
+        );
+        assert_eq!((None, Some(a.clone())), a.split(0, 0));
+        assert_eq!((Some(a.clone()), None), a.split(0, 10));
+
+        let b = rect([5..15, 2..8]);
+        assert_eq!(Some(rect([5..10, 2..4])), a.intersection(&b));
+        assert_eq!(None, a.intersection(&rect([10..12, 0..4])));
+        assert!(a.contains(&[9, 3]));
+        assert!(!a.contains(&[10, 3]));
+        assert!(!a.contains(&[9]));
+        assert_eq!(None, a.intersection(&rect([0..10, 0..4, 0..1])));
+        assert_eq!(None, HyperRect::new(vec![0, 0], vec![1]));
+        assert_eq!("[0, 10) x [0, 4)", a.to_string());
+    }
+
+    #[test]
+    fn test_difference() {
+        let outer = rect([0..4, 0..4, 0..4]);
+        let inner = rect([1..3, 1..3, 1..3]);
+        let pieces = outer.difference(&inner);
+
+        assert_eq!(6, pieces.len());
+        assert_eq!(64 - 8, pieces.iter().map(HyperRect::volume).sum::<u128>());
+        for (i, a) in pieces.iter().enumerate() {
+            assert!(!a.intersects(&inner));
+            for b in &pieces[i + 1..] {
+                assert!(!a.intersects(b));
+            }
+        }
+
+        assert!(inner.difference(&outer).is_empty());
+        assert_eq!(
+            vec![inner.clone()],
+            inner.difference(&rect([5..6, 0..4, 0..4]))
+        );
+
+        let wide = rect([0..1 << 40, 0..1 << 40, 0..1 << 40]);
+        assert_eq!(Some(1 << 120), wide.checked_volume());
+        assert_eq!(
+            None,
+            rect([0..1 << 40, 0..1 << 40, 0..1 << 50]).checked_volume()
+        );
+    }
+
+    #[test]
+    fn test_disjoint_union_volume() {
+        let union: DisjointUnion = [
+            rect([0..4, 0..4]),
+            rect([2..6, 2..6]),
+            rect([1..2, 1..2]),
+            rect([10..11, 0..3]),
+        ]
+        .into_iter()
+        .collect();
+
+        // Two 4x4 squares overlapping in a 2x2 square, plus a separate 1x3 strip.
+        assert_eq!(16 + 16 - 4 + 3, union.volume());
+        assert!(union.contains(&[5, 5]));
+        assert!(!union.contains(&[5, 0]));
+    }
+}
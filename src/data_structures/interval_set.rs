@@ -0,0 +1,362 @@
+use crate::AdventErr::Compute;
+use crate::AdventResult;
+use num::PrimInt;
+use std::fmt::{Display, Formatter};
+use std::ops::Range;
+
+/// A set of integers, stored as half-open ranges.
+///
+/// The ranges are always normalised: sorted, non-empty,
+/// and separated by at least one value not in the set.
+/// Overlapping or touching ranges are merged as they are added,
+/// so two sets containing the same values compare equal.
+#[derive(Debug, Clone, Eq, PartialEq, Hash)]
+pub struct IntervalSet<T> {
+    intervals: Vec<Range<T>>,
+}
+
+#[allow(dead_code)]
+impl<T: PrimInt> IntervalSet<T> {
+    pub fn new() -> Self {
+        Self { intervals: vec![] }
+    }
+
+    /// Builds a set from ranges in any order. Ranges may overlap, and empty ranges are ignored.
+    pub fn from_ranges(ranges: impl IntoIterator<Item = Range<T>>) -> Self {
+        let mut ranges: Vec<_> = ranges.into_iter().filter(|r| r.start < r.end).collect();
+        ranges.sort_unstable_by_key(|r| r.start);
+
+        let mut intervals: Vec<Range<T>> = Vec::with_capacity(ranges.len());
+        for range in ranges {
+            match intervals.last_mut() {
+                Some(last) if range.start <= last.end => last.end = last.end.max(range.end),
+                _ => intervals.push(range),
+            }
+        }
+
+        Self { intervals }
+    }
+
+    pub fn intervals(&self) -> &[Range<T>] {
+        &self.intervals
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.intervals.is_empty()
+    }
+
+    /// The smallest value in the set.
+    pub fn min(&self) -> Option<T> {
+        self.intervals.first().map(|r| r.start)
+    }
+
+    /// The largest value in the set.
+    pub fn max(&self) -> Option<T> {
+        self.intervals.last().map(|r| r.end - T::one())
+    }
+
+    /// The smallest single range covering the whole set.
+    pub fn span(&self) -> Option<Range<T>> {
+        Some(self.intervals.first()?.start..self.intervals.last()?.end)
+    }
+
+    /// The number of values in the set.
+    pub fn total_length(&self) -> T {
+        self.intervals
+            .iter()
+            .fold(T::zero(), |acc, r| acc + (r.end - r.start))
+    }
+
+    /// Index of the first interval which ends after `value`.
+    fn first_ending_after(&self, value: T) -> usize {
+        self.intervals.partition_point(|r| r.end <= value)
+    }
+
+    pub fn contains(&self, value: T) -> bool {
+        self.intervals
+            .get(self.first_ending_after(value))
+            .is_some_and(|r| r.start <= value)
+    }
+
+    /// Whether every value of `range` is in the set. Empty ranges are always contained.
+    pub fn contains_range(&self, range: &Range<T>) -> bool {
+        range.start >= range.end
+            || self
+                .intervals
+                .get(self.first_ending_after(range.start))
+                .is_some_and(|r| r.start <= range.start && range.end <= r.end)
+    }
+
+    pub fn is_subset(&self, other: &Self) -> bool {
+        self.intervals.iter().all(|r| other.contains_range(r))
+    }
+
+    pub fn insert(&mut self, range: Range<T>) {
+        *self = self.union(&Self::from(range));
+    }
+
+    pub fn union(&self, other: &Self) -> Self {
+        Self::from_ranges(self.intervals.iter().chain(&other.intervals).cloned())
+    }
+
+    pub fn intersection(&self, other: &Self) -> Self {
+        let mut intervals = vec![];
+        let (mut i, mut j) = (0, 0);
+        while let (Some(a), Some(b)) = (self.intervals.get(i), other.intervals.get(j)) {
+            let start = a.start.max(b.start);
+            let end = a.end.min(b.end);
+            if start < end {
+                intervals.push(start..end);
+            }
+
+            // Whichever interval ends first cannot overlap anything further.
+            if a.end <= b.end {
+                i += 1;
+            } else {
+                j += 1;
+            }
+        }
+
+        // Pieces of disjoint, non-touching intervals are themselves disjoint and non-touching.
+        Self { intervals }
+    }
+
+    /// Values in `self` but not in `other`.
+    pub fn difference(&self, other: &Self) -> Self {
+        match self.span() {
+            Some(span) => self.intersection(&other.complement_within(span)),
+            None => Self::new(),
+        }
+    }
+
+    /// Values in `bounds` which are not in the set.
+    pub fn complement_within(&self, bounds: Range<T>) -> Self {
+        let mut intervals = vec![];
+        let mut start = bounds.start;
+        for r in &self.intervals {
+            if r.start >= bounds.end {
+                break;
+            }
+
+            if start < r.start {
+                intervals.push(start..r.start);
+            }
+            start = start.max(r.end);
+        }
+
+        if start < bounds.end {
+            intervals.push(start..bounds.end);
+        }
+
+        Self { intervals }
+    }
+}
+
+impl<T: PrimInt> Default for IntervalSet<T> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<T: PrimInt> From<Range<T>> for IntervalSet<T> {
+    fn from(value: Range<T>) -> Self {
+        Self::from_ranges([value])
+    }
+}
+
+impl<T: PrimInt> FromIterator<Range<T>> for IntervalSet<T> {
+    fn from_iter<I: IntoIterator<Item = Range<T>>>(iter: I) -> Self {
+        Self::from_ranges(iter)
+    }
+}
+
+/// e.g. `{[1, 3), [5, 9)}`
+impl<T: Display> Display for IntervalSet<T> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{{")?;
+        for (i, r) in self.intervals.iter().enumerate() {
+            if i > 0 {
+                write!(f, ", ")?;
+            }
+            write!(f, "[{}, {})", r.start, r.end)?;
+        }
+        write!(f, "}}")
+    }
+}
+
+/// Shifts every value of `source` so that `source.start` lands on `destination_start`.
+#[derive(Debug, Clone, Eq, PartialEq)]
+pub struct LinearSegment<T> {
+    pub source: Range<T>,
+    pub destination_start: T,
+}
+
+impl<T: PrimInt> LinearSegment<T> {
+    /// `value` must lie in `source`.
+    fn map_value_unchecked(&self, value: T) -> T {
+        self.destination_start + (value - self.source.start)
+    }
+}
+
+/// A function made up of non-overlapping shifted ranges.
+/// Values outside every segment map to themselves.
+#[derive(Debug, Clone, Eq, PartialEq)]
+pub struct PiecewiseLinearMap<T> {
+    /// Sorted by source start
+    segments: Vec<LinearSegment<T>>,
+}
+
+#[allow(dead_code)]
+impl<T: PrimInt> PiecewiseLinearMap<T> {
+    /// Fails if any two source ranges overlap.
+    pub fn new(mut segments: Vec<LinearSegment<T>>) -> AdventResult<Self> {
+        segments.retain(|segment| segment.source.start < segment.source.end);
+        segments.sort_unstable_by_key(|segment| segment.source.start);
+
+        if segments
+            .windows(2)
+            .any(|pair| pair[1].source.start < pair[0].source.end)
+        {
+            return Err(Compute(String::from(
+                "Piecewise linear map has overlapping source ranges",
+            )));
+        }
+
+        Ok(Self { segments })
+    }
+
+    pub fn segments(&self) -> &[LinearSegment<T>] {
+        &self.segments
+    }
+
+    pub fn map_value(&self, value: T) -> T {
+        let index = self
+            .segments
+            .partition_point(|segment| segment.source.end <= value);
+        match self.segments.get(index) {
+            Some(segment) if segment.source.start <= value => segment.map_value_unchecked(value),
+            _ => value,
+        }
+    }
+
+    /// The image of every value in `set`.
+    pub fn map_set(&self, set: &IntervalSet<T>) -> IntervalSet<T> {
+        let mut output = vec![];
+        for range in set.intervals() {
+            let mut start = range.start;
+            let mut index = self
+                .segments
+                .partition_point(|segment| segment.source.end <= start);
+
+            while start < range.end {
+                match self.segments.get(index) {
+                    Some(segment) if segment.source.start <= start => {
+                        let end = range.end.min(segment.source.end);
+                        output.push(
+                            segment.map_value_unchecked(start)
+                                ..segment.map_value_unchecked(start) + (end - start),
+                        );
+                        start = end;
+                        index += 1;
+                    }
+
+                    Some(next_segment) => {
+                        // These values map to themselves.
+                        let end = range.end.min(next_segment.source.start);
+                        output.push(start..end);
+                        start = end;
+                    }
+
+                    None => {
+                        output.push(start..range.end);
+                        start = range.end;
+                    }
+                }
+            }
+        }
+
+        IntervalSet::from_ranges(output)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn set(ranges: &[Range<i64>]) -> IntervalSet<i64> {
+        ranges.iter().cloned().collect()
+    }
+
+    #[test]
+    fn test_normalisation() {
+        let s = set(&[5..9, 1..3, 2..4, 9..10, 7..8, 12..12]);
+        assert_eq!(&[1..4, 5..10], s.intervals());
+        assert_eq!(8, s.total_length());
+        assert_eq!(Some(1), s.min());
+        assert_eq!(Some(9), s.max());
+        assert_eq!("{[1, 4), [5, 10)}", s.to_string());
+
+        assert!(s.contains(1));
+        assert!(s.contains(3));
+        assert!(!s.contains(4));
+        assert!(!s.contains(10));
+        assert!(s.contains_range(&(5..10)));
+        assert!(!s.contains_range(&(3..6)));
+        assert!(set(&[2..3, 6..8]).is_subset(&s));
+        assert!(!IntervalSet::from(2..5).is_subset(&s));
+    }
+
+    #[test]
+    fn test_set_operations() {
+        let a = set(&[0..10, 20..30]);
+        let b = set(&[5..25, 28..40]);
+
+        assert_eq!(IntervalSet::from(0..40), a.union(&b));
+        assert_eq!(set(&[5..10, 20..25, 28..30]), a.intersection(&b));
+        assert_eq!(set(&[0..5, 25..28]), a.difference(&b));
+        assert_eq!(set(&[10..20, 30..40]), b.difference(&a));
+        assert_eq!(set(&[-5..0, 10..20, 30..35]), a.complement_within(-5..35));
+        assert_eq!(IntervalSet::from(12..18), a.complement_within(12..18));
+        assert!(a.difference(&a).is_empty());
+
+        let mut c = a.clone();
+        c.insert(10..20);
+        assert_eq!(IntervalSet::from(0..30), c);
+    }
+
+    #[test]
+    fn test_piecewise_linear_map() {
+        let map = PiecewiseLinearMap::new(vec![
+            LinearSegment {
+                source: 50..52,
+                destination_start: 0,
+            },
+            LinearSegment {
+                source: 10..20,
+                destination_start: 100,
+            },
+        ])
+        .unwrap();
+
+        assert_eq!(105, map.map_value(15));
+        assert_eq!(1, map.map_value(51));
+        assert_eq!(20, map.map_value(20));
+
+        assert_eq!(
+            set(&[0..2, 8..10, 20..30, 45..50, 52..55, 100..110]),
+            map.map_set(&set(&[8..30, 45..55]))
+        );
+
+        let overlapping = PiecewiseLinearMap::new(vec![
+            LinearSegment {
+                source: 0..5,
+                destination_start: 10,
+            },
+            LinearSegment {
+                source: 4..6,
+                destination_start: 20,
+            },
+        ]);
+        assert!(overlapping.is_err());
+    }
+}
@@ -0,0 +1,207 @@
+use crate::AdventErr::Compute;
+use crate::AdventResult;
+use std::collections::{HashMap, VecDeque};
+
+/// One occurrence of a pattern in a text.
+/// Positions are byte offsets, with `end` exclusive.
+#[derive(Debug, Eq, PartialEq)]
+pub struct PatternMatch<'a, V> {
+    pub start: usize,
+    pub end: usize,
+    pub value: &'a V,
+}
+
+// Only the reference is copied, so unlike the derived impls, these don't need `V: Copy`.
+impl<V> Clone for PatternMatch<'_, V> {
+    fn clone(&self) -> Self {
+        *self
+    }
+}
+
+impl<V> Copy for PatternMatch<'_, V> {}
+
+/// Finds every occurrence of any of a dictionary of patterns in a single pass over a text,
+/// by the Aho-Corasick algorithm. Each pattern carries a value, which is reported with its matches.
+///
+/// Overlapping matches are all found, so `"eightwo"` matches both `"eight"` and `"two"`.
+#[derive(Debug, Clone)]
+pub struct PatternMatcher<V> {
+    nodes: Vec<Node>,
+    /// Length and value of each pattern
+    patterns: Vec<(usize, V)>,
+}
+
+/// A state of the automaton, for having just read some prefix of a pattern.
+#[derive(Debug, Clone, Default)]
+struct Node {
+    children: HashMap<u8, usize>,
+    /// The state for the longest proper suffix of this prefix which is also a prefix
+    fail: usize,
+    /// Patterns ending here, including those which are suffixes of this prefix
+    outputs: Vec<usize>,
+}
+
+const ROOT: usize = 0;
+
+#[allow(dead_code)]
+impl<V> PatternMatcher<V> {
+    /// Fails if any pattern is empty or appears twice.
+    pub fn new<S: AsRef<str>>(dictionary: impl IntoIterator<Item = (S, V)>) -> AdventResult<Self> {
+        let mut nodes = vec![Node::default()];
+        let mut patterns = vec![];
+
+        // Build the trie.
+        for (pattern, value) in dictionary {
+            let pattern = pattern.as_ref();
+            if pattern.is_empty() {
+                return Err(Compute(String::from("Patterns must not be empty")));
+            }
+
+            let mut current = ROOT;
+            for &byte in pattern.as_bytes() {
+                current = match nodes[current].children.get(&byte) {
+                    Some(&child) => child,
+                    None => {
+                        nodes.push(Node::default());
+                        let child = nodes.len() - 1;
+                        nodes[current].children.insert(byte, child);
+                        child
+                    }
+                };
+            }
+
+            if !nodes[current].outputs.is_empty() {
+                return Err(Compute(format!("Duplicate pattern \"{pattern}\"")));
+            }
+            nodes[current].outputs.push(patterns.len());
+            patterns.push((pattern.len(), value));
+        }
+
+        // Breadth first, so each node's suffix is linked before the node itself.
+        let mut queue: VecDeque<_> = nodes[ROOT].children.values().copied().collect();
+        while let Some(current) = queue.pop_front() {
+            let children: Vec<_> = nodes[current]
+                .children
+                .iter()
+                .map(|(&byte, &child)| (byte, child))
+                .collect();
+            for (byte, child) in children {
+                let fail = if current == ROOT {
+                    ROOT
+                } else {
+                    Self::next_state(&nodes, nodes[current].fail, byte)
+                };
+                let inherited = nodes[fail].outputs.clone();
+                nodes[child].fail = fail;
+                nodes[child].outputs.extend(inherited);
+                queue.push_back(child);
+            }
+        }
+
+        Ok(Self { nodes, patterns })
+    }
+
+    pub fn n_patterns(&self) -> usize {
+        self.patterns.len()
+    }
+
+    fn next_state(nodes: &[Node], mut state: usize, byte: u8) -> usize {
+        loop {
+            if let Some(&child) = nodes[state].children.get(&byte) {
+                return child;
+            }
+            if state == ROOT {
+                return ROOT;
+            }
+            state = nodes[state].fail;
+        }
+    }
+
+    /// Every match in the text, in order of where they end.
+    /// Matches ending at the same place are longest first.
+    pub fn find_all<'a>(&'a self, text: &str) -> Vec<PatternMatch<'a, V>> {
+        let mut state = ROOT;
+        let mut matches = vec![];
+        for (index, &byte) in text.as_bytes().iter().enumerate() {
+            state = Self::next_state(&self.nodes, state, byte);
+            let end = index + 1;
+            for &pattern in &self.nodes[state].outputs {
+                let (length, value) = &self.patterns[pattern];
+                matches.push(PatternMatch {
+                    start: end - length,
+                    end,
+                    value,
+                });
+            }
+        }
+
+        matches
+    }
+
+    /// The match starting earliest. Ties go to the shortest.
+    pub fn first<'a>(&'a self, text: &str) -> Option<PatternMatch<'a, V>> {
+        self.first_and_last(text).map(|(first, _)| first)
+    }
+
+    /// The match starting latest. Ties go to the longest.
+    pub fn last<'a>(&'a self, text: &str) -> Option<PatternMatch<'a, V>> {
+        self.first_and_last(text).map(|(_, last)| last)
+    }
+
+    /// Both `first` and `last`, from a single pass over the text.
+    pub fn first_and_last<'a>(
+        &'a self,
+        text: &str,
+    ) -> Option<(PatternMatch<'a, V>, PatternMatch<'a, V>)> {
+        let matches = self.find_all(text);
+        let first = matches.iter().min_by_key(|m| (m.start, m.end))?;
+        let last = matches.iter().max_by_key(|m| (m.start, m.end))?;
+        Some((*first, *last))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn found<'a>(matches: &[PatternMatch<'a, u32>]) -> Vec<(usize, u32)> {
+        matches.iter().map(|m| (m.start, *m.value)).collect()
+    }
+
+    #[test]
+    fn test_overlapping_words() {
+        let matcher =
+            PatternMatcher::new([("one", 1), ("two", 2), ("eight", 8), ("ten", 10), ("1", 1)])
+                .unwrap();
+
+        let matches = matcher.find_all("xeightwone1ten");
+        assert_eq!(
+            vec![(1, 8), (5, 2), (7, 1), (10, 1), (11, 10)],
+            found(&matches)
+        );
+        assert_eq!((5, 8), (matches[1].start, matches[1].end));
+
+        assert_eq!(8, *matcher.first("xeightwo").unwrap().value);
+        assert_eq!(2, *matcher.last("xeightwo").unwrap().value);
+        assert_eq!(None, matcher.first("nothing here"));
+
+        let (first, last) = matcher.first_and_last("ten1").unwrap();
+        assert_eq!((10, 1), (*first.value, *last.value));
+        assert_eq!(None, matcher.first_and_last("nothing here"));
+    }
+
+    #[test]
+    fn test_nested_patterns() {
+        // Patterns which are suffixes and prefixes of each other
+        let matcher =
+            PatternMatcher::new([("he", 0), ("she", 1), ("his", 2), ("hers", 3)]).unwrap();
+        let matches = matcher.find_all("ushers");
+        assert_eq!(vec![(1, 1), (2, 0), (2, 3)], found(&matches));
+
+        assert_eq!(1, *matcher.first("ushers").unwrap().value);
+        assert_eq!(3, *matcher.last("ushers").unwrap().value);
+
+        assert!(PatternMatcher::new([("", 0)]).is_err());
+        assert!(PatternMatcher::new([("a", 0), ("a", 1)]).is_err());
+    }
+}
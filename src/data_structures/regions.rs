@@ -0,0 +1,238 @@
+use crate::data_structures::{Grid2D, GridPoint2D};
+use std::cmp::{max, min};
+
+/// Which neighbouring cells are considered adjacent.
+#[allow(dead_code)]
+#[derive(Debug, Copy, Clone, Eq, PartialEq)]
+pub enum Connectivity {
+    /// Up, down, left and right.
+    Four,
+    /// As `Four`, plus the diagonals.
+    Eight,
+}
+
+impl Connectivity {
+    fn offsets(self) -> &'static [(isize, isize)] {
+        const FOUR: [(isize, isize); 4] = [(-1, 0), (0, -1), (0, 1), (1, 0)];
+        const EIGHT: [(isize, isize); 8] = [
+            (-1, -1),
+            (-1, 0),
+            (-1, 1),
+            (0, -1),
+            (0, 1),
+            (1, -1),
+            (1, 0),
+            (1, 1),
+        ];
+
+        match self {
+            Connectivity::Four => &FOUR,
+            Connectivity::Eight => &EIGHT,
+        }
+    }
+}
+
+#[derive(Debug, Copy, Clone, Eq, PartialEq, Hash, Ord, PartialOrd)]
+pub struct ComponentId(pub usize);
+
+/// Summary of a single connected component.
+/// `min` and `max` are the inclusive corners of its bounding box.
+#[derive(Debug, Copy, Clone, Eq, PartialEq)]
+pub struct Component {
+    pub id: ComponentId,
+    pub size: usize,
+    pub min: GridPoint2D,
+    pub max: GridPoint2D,
+    pub touches_border: bool,
+}
+
+#[derive(Debug, Clone)]
+pub struct Components {
+    /// Cells which were not included have no label.
+    pub labels: Grid2D<Option<ComponentId>>,
+    /// Indexed by `ComponentId`.
+    pub components: Vec<Component>,
+}
+
+impl Components {
+    #[allow(dead_code)]
+    pub fn get(&self, id: ComponentId) -> Option<&Component> {
+        self.components.get(id.0)
+    }
+
+    #[allow(dead_code)]
+    pub fn component_at(&self, point: GridPoint2D) -> Option<&Component> {
+        self.labels
+            .get(point)
+            .copied()
+            .flatten()
+            .and_then(|id| self.get(id))
+    }
+}
+
+impl<T> Grid2D<T> {
+    fn neighbours(
+        &self,
+        point: GridPoint2D,
+        connectivity: Connectivity,
+    ) -> impl Iterator<Item = GridPoint2D> + '_ {
+        connectivity
+            .offsets()
+            .iter()
+            .filter_map(move |&(d_row, d_col)| {
+                let neighbour = GridPoint2D::new(
+                    point.row.checked_add_signed(d_row)?,
+                    point.col.checked_add_signed(d_col)?,
+                );
+                self.in_bounds(neighbour).then_some(neighbour)
+            })
+    }
+
+    fn is_border(&self, point: GridPoint2D) -> bool {
+        point.row == 0
+            || point.col == 0
+            || point.row + 1 == self.n_rows()
+            || point.col + 1 == self.n_cols()
+    }
+
+    /// Returns every point connected to `start` through cells satisfying `include`,
+    /// in the order they were reached.
+    /// The result is empty if `start` is out of bounds or does not satisfy `include` itself.
+    #[allow(dead_code)]
+    pub fn flood_fill(
+        &self,
+        start: GridPoint2D,
+        connectivity: Connectivity,
+        include: impl Fn(&T) -> bool,
+    ) -> Vec<GridPoint2D> {
+        let mut visited = Grid2D::new(self.n_rows(), self.n_cols(), false);
+        self.flood_fill_into(start, connectivity, &include, &mut visited)
+    }
+
+    fn flood_fill_into(
+        &self,
+        start: GridPoint2D,
+        connectivity: Connectivity,
+        include: &impl Fn(&T) -> bool,
+        visited: &mut Grid2D<bool>,
+    ) -> Vec<GridPoint2D> {
+        match self.get(start) {
+            Some(value) if include(value) && !*visited.get_unchecked(start) => {}
+            _ => return vec![],
+        }
+
+        *visited.get_mut_unchecked(start) = true;
+        let mut filled = vec![start];
+        let mut next_index = 0;
+        while let Some(&point) = filled.get(next_index) {
+            next_index += 1;
+
+            for neighbour in self.neighbours(point, connectivity) {
+                let seen = visited.get_mut_unchecked(neighbour);
+                if !*seen && include(self.get_unchecked(neighbour)) {
+                    *seen = true;
+                    filled.push(neighbour);
+                }
+            }
+        }
+
+        filled
+    }
+
+    /// Labels each maximal connected group of cells satisfying `include`.
+    /// Components are numbered in row-major order of their first cell.
+    #[allow(dead_code)]
+    pub fn label_components(
+        &self,
+        connectivity: Connectivity,
+        include: impl Fn(&T) -> bool,
+    ) -> Components {
+        let mut labels = Grid2D::new(self.n_rows(), self.n_cols(), None);
+        let mut visited = Grid2D::new(self.n_rows(), self.n_cols(), false);
+        let mut components = vec![];
+
+        for row in 0..self.n_rows() {
+            for col in 0..self.n_cols() {
+                let start = GridPoint2D::new(row, col);
+                let filled = self.flood_fill_into(start, connectivity, &include, &mut visited);
+                if filled.is_empty() {
+                    continue;
+                }
+
+                let id = ComponentId(components.len());
+                let mut component = Component {
+                    id,
+                    size: filled.len(),
+                    min: start,
+                    max: start,
+                    touches_border: false,
+                };
+
+                for point in filled {
+                    *labels.get_mut_unchecked(point) = Some(id);
+                    component.min = GridPoint2D::new(
+                        min(component.min.row, point.row),
+                        min(component.min.col, point.col),
+                    );
+                    component.max = GridPoint2D::new(
+                        max(component.max.row, point.row),
+                        max(component.max.col, point.col),
+                    );
+                    component.touches_border |= self.is_border(point);
+                }
+
+                components.push(component);
+            }
+        }
+
+        Components { labels, components }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::parser;
+
+    fn grid(input: &str) -> Grid2D<char> {
+        parser::as_grid2d_by_char_from_str(input, Ok).unwrap()
+    }
+
+    #[test]
+    fn test_flood_fill_connectivity() {
+        let grid = grid("#..\n.#.\n..#");
+
+        let four = grid.flood_fill(GridPoint2D::new(0, 1), Connectivity::Four, |&c| c == '.');
+        assert_eq!(3, four.len());
+
+        let eight = grid.flood_fill(GridPoint2D::new(0, 1), Connectivity::Eight, |&c| c == '.');
+        assert_eq!(6, eight.len());
+
+        let on_wall = grid.flood_fill(GridPoint2D::new(0, 0), Connectivity::Four, |&c| c == '.');
+        assert!(on_wall.is_empty());
+    }
+
+    #[test]
+    fn test_label_components() {
+        let grid = grid("#####\n#..##\n#####\n.#..#");
+        let components = grid.label_components(Connectivity::Four, |&c| c == '.');
+
+        assert_eq!(3, components.components.len());
+
+        let inner = components.component_at(GridPoint2D::new(1, 2)).unwrap();
+        assert_eq!(ComponentId(0), inner.id);
+        assert_eq!(2, inner.size);
+        assert_eq!(GridPoint2D::new(1, 1), inner.min);
+        assert_eq!(GridPoint2D::new(1, 2), inner.max);
+        assert!(!inner.touches_border);
+
+        let corner = components.component_at(GridPoint2D::new(3, 0)).unwrap();
+        assert_eq!(ComponentId(1), corner.id);
+        assert!(corner.touches_border);
+
+        assert_eq!(
+            None,
+            *components.labels.get_unchecked(GridPoint2D::new(0, 0))
+        );
+    }
+}
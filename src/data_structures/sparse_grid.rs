@@ -0,0 +1,333 @@
+use crate::data_structures::{Direction, Grid2D, GridPoint2D};
+use std::cmp::{max, min};
+use std::collections::HashMap;
+use std::fmt::{Display, Formatter};
+use std::ops::{Add, AddAssign, Sub};
+
+/// A grid point which may lie at any (possibly negative) position.
+#[derive(Debug, Default, Copy, Clone, Hash, Eq, PartialEq, Ord, PartialOrd)]
+pub struct SparsePoint2D {
+    pub row: i64,
+    pub col: i64,
+}
+
+impl SparsePoint2D {
+    pub fn new(row: i64, col: i64) -> Self {
+        Self { row, col }
+    }
+
+    #[allow(dead_code)]
+    pub fn manhattan_distance(&self, other: Self) -> u64 {
+        self.row.abs_diff(other.row) + self.col.abs_diff(other.col)
+    }
+
+    #[allow(dead_code)]
+    pub fn move_direction(self, direction: Direction, distance: i64) -> Self {
+        match direction {
+            Direction::Up => Self::new(self.row - distance, self.col),
+            Direction::Down => Self::new(self.row + distance, self.col),
+            Direction::Left => Self::new(self.row, self.col - distance),
+            Direction::Right => Self::new(self.row, self.col + distance),
+        }
+    }
+}
+
+impl Add for SparsePoint2D {
+    type Output = SparsePoint2D;
+
+    fn add(self, rhs: Self) -> Self::Output {
+        Self::new(self.row + rhs.row, self.col + rhs.col)
+    }
+}
+
+impl AddAssign for SparsePoint2D {
+    fn add_assign(&mut self, rhs: Self) {
+        *self = self.add(rhs)
+    }
+}
+
+impl Sub for SparsePoint2D {
+    type Output = SparsePoint2D;
+
+    fn sub(self, rhs: Self) -> Self::Output {
+        Self::new(self.row - rhs.row, self.col - rhs.col)
+    }
+}
+
+impl From<GridPoint2D> for SparsePoint2D {
+    fn from(value: GridPoint2D) -> Self {
+        Self::new(value.row as i64, value.col as i64)
+    }
+}
+
+impl Display for SparsePoint2D {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(f, "({}, {})", self.row, self.col)
+    }
+}
+
+/// Inclusive rectangle of [`SparsePoint2D`]s.
+#[derive(Debug, Copy, Clone, Eq, PartialEq, Hash)]
+pub struct BoundingBox {
+    pub min: SparsePoint2D,
+    pub max: SparsePoint2D,
+}
+
+impl BoundingBox {
+    fn single(point: SparsePoint2D) -> Self {
+        Self {
+            min: point,
+            max: point,
+        }
+    }
+
+    fn expand(self, point: SparsePoint2D) -> Self {
+        Self {
+            min: SparsePoint2D::new(min(self.min.row, point.row), min(self.min.col, point.col)),
+            max: SparsePoint2D::new(max(self.max.row, point.row), max(self.max.col, point.col)),
+        }
+    }
+
+    fn on_edge(self, point: SparsePoint2D) -> bool {
+        point.row == self.min.row
+            || point.row == self.max.row
+            || point.col == self.min.col
+            || point.col == self.max.col
+    }
+
+    #[allow(dead_code)]
+    pub fn contains(self, point: SparsePoint2D) -> bool {
+        (self.min.row..=self.max.row).contains(&point.row)
+            && (self.min.col..=self.max.col).contains(&point.col)
+    }
+
+    pub fn n_rows(self) -> usize {
+        (self.max.row - self.min.row + 1) as usize
+    }
+
+    pub fn n_cols(self) -> usize {
+        (self.max.col - self.min.col + 1) as usize
+    }
+}
+
+/// An unbounded 2D grid, storing only the cells which have been set.
+/// The bounding box of all set cells is kept up to date on insertion and removal.
+#[derive(Debug, Clone, Eq, PartialEq)]
+pub struct SparseGrid<T> {
+    cells: HashMap<SparsePoint2D, T>,
+    bounds: Option<BoundingBox>,
+}
+
+impl<T> Default for SparseGrid<T> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<T> SparseGrid<T> {
+    pub fn new() -> Self {
+        Self {
+            cells: HashMap::new(),
+            bounds: None,
+        }
+    }
+
+    #[allow(dead_code)]
+    pub fn len(&self) -> usize {
+        self.cells.len()
+    }
+
+    #[allow(dead_code)]
+    pub fn is_empty(&self) -> bool {
+        self.cells.is_empty()
+    }
+
+    /// Returns `None` if the grid is empty.
+    #[allow(dead_code)]
+    pub fn bounds(&self) -> Option<BoundingBox> {
+        self.bounds
+    }
+
+    pub fn get(&self, point: SparsePoint2D) -> Option<&T> {
+        self.cells.get(&point)
+    }
+
+    #[allow(dead_code)]
+    pub fn get_mut(&mut self, point: SparsePoint2D) -> Option<&mut T> {
+        self.cells.get_mut(&point)
+    }
+
+    #[allow(dead_code)]
+    pub fn contains(&self, point: SparsePoint2D) -> bool {
+        self.cells.contains_key(&point)
+    }
+
+    /// Returns the previous value at `point`, if there was one.
+    pub fn insert(&mut self, point: SparsePoint2D, value: T) -> Option<T> {
+        self.bounds = Some(match self.bounds {
+            None => BoundingBox::single(point),
+            Some(bounds) => bounds.expand(point),
+        });
+
+        self.cells.insert(point, value)
+    }
+
+    /// Removing a point on the edge of the bounding box requires
+    /// a scan of all remaining cells to shrink the box.
+    #[allow(dead_code)]
+    pub fn remove(&mut self, point: SparsePoint2D) -> Option<T> {
+        let removed = self.cells.remove(&point)?;
+
+        if self.bounds.is_some_and(|bounds| bounds.on_edge(point)) {
+            self.bounds = self
+                .cells
+                .keys()
+                .fold(None, |acc: Option<BoundingBox>, &point| {
+                    Some(match acc {
+                        None => BoundingBox::single(point),
+                        Some(bounds) => bounds.expand(point),
+                    })
+                });
+        }
+
+        Some(removed)
+    }
+
+    /// Iterates over set cells ordered by row, then by column.
+    #[allow(dead_code)]
+    pub fn iter(&self) -> impl Iterator<Item = (SparsePoint2D, &T)> {
+        let mut points: Vec<_> = self.cells.keys().copied().collect();
+        points.sort_unstable();
+        points
+            .into_iter()
+            .map(|point| (point, self.cells.get(&point).unwrap()))
+    }
+
+    /// Builds a sparse grid from a dense grid, placing the dense grid's `(0, 0)` at `origin`.
+    /// Only cells for which `keep` returns `true` are stored.
+    #[allow(dead_code)]
+    pub fn from_grid2d(grid: Grid2D<T>, origin: SparsePoint2D, keep: impl Fn(&T) -> bool) -> Self {
+        let n_cols = grid.n_cols();
+        let mut result = Self::new();
+        for (index, value) in grid.into_cells().enumerate() {
+            if keep(&value) {
+                let offset = SparsePoint2D::new((index / n_cols) as i64, (index % n_cols) as i64);
+                result.insert(origin + offset, value);
+            }
+        }
+
+        result
+    }
+}
+
+impl<T> SparseGrid<T>
+where
+    T: Clone,
+{
+    /// Creates a dense grid covering the bounding box, with unset cells filled by `fill`.
+    /// Also returns the position of the dense grid's `(0, 0)`.
+    ///
+    /// Returns `None` if the grid is empty.
+    #[allow(dead_code)]
+    pub fn to_grid2d(&self, fill: T) -> Option<(Grid2D<T>, SparsePoint2D)> {
+        let bounds = self.bounds?;
+        let mut grid = Grid2D::new(bounds.n_rows(), bounds.n_cols(), fill);
+        for (&point, value) in &self.cells {
+            let offset = point - bounds.min;
+            *grid.get_mut_unchecked(GridPoint2D::new(offset.row as usize, offset.col as usize)) =
+                value.clone();
+        }
+
+        Some((grid, bounds.min))
+    }
+}
+
+/// Unset cells are displayed as `T::default()`.
+impl<T> Display for SparseGrid<T>
+where
+    T: Display + Default,
+{
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        let Some(bounds) = self.bounds else {
+            return Ok(());
+        };
+
+        let empty = T::default();
+        for row in bounds.min.row..=bounds.max.row {
+            for col in bounds.min.col..=bounds.max.col {
+                self.get(SparsePoint2D::new(row, col))
+                    .unwrap_or(&empty)
+                    .fmt(f)?;
+            }
+
+            if row < bounds.max.row {
+                writeln!(f)?;
+            }
+        }
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_bounds_track_insert_and_remove() {
+        let mut grid = SparseGrid::new();
+        assert_eq!(None, grid.bounds());
+
+        grid.insert(SparsePoint2D::new(-3, 2), 'a');
+        grid.insert(SparsePoint2D::new(5, -1), 'b');
+        grid.insert(SparsePoint2D::new(0, 0), 'c');
+        assert_eq!(
+            Some(BoundingBox {
+                min: SparsePoint2D::new(-3, -1),
+                max: SparsePoint2D::new(5, 2),
+            }),
+            grid.bounds()
+        );
+
+        assert_eq!(Some('b'), grid.remove(SparsePoint2D::new(5, -1)));
+        assert_eq!(
+            Some(BoundingBox {
+                min: SparsePoint2D::new(-3, 0),
+                max: SparsePoint2D::new(0, 2),
+            }),
+            grid.bounds()
+        );
+
+        grid.remove(SparsePoint2D::new(-3, 2));
+        grid.remove(SparsePoint2D::new(0, 0));
+        assert_eq!(None, grid.bounds());
+    }
+
+    #[test]
+    fn test_iter_row_order() {
+        let mut grid = SparseGrid::new();
+        for (row, col) in [(2, 0), (-1, 4), (2, -7), (0, 0), (-1, -1)] {
+            grid.insert(SparsePoint2D::new(row, col), (row, col));
+        }
+
+        let order: Vec<_> = grid.iter().map(|(_, &value)| value).collect();
+        assert_eq!(vec![(-1, -1), (-1, 4), (0, 0), (2, -7), (2, 0)], order);
+    }
+
+    #[test]
+    fn test_dense_round_trip() {
+        let dense = Grid2D::from(vec![1, 0, 2, 0, 3, 0], 2, 3);
+        let origin = SparsePoint2D::new(-10, 7);
+        let sparse = SparseGrid::from_grid2d(dense, origin, |&value| value != 0);
+
+        assert_eq!(3, sparse.len());
+        assert_eq!(Some(&3), sparse.get(SparsePoint2D::new(-9, 8)));
+
+        let (dense, dense_origin) = sparse.to_grid2d(0).unwrap();
+        assert_eq!(origin, dense_origin);
+        assert_eq!(2, dense.n_rows());
+        assert_eq!(3, dense.n_cols());
+        assert_eq!("102\n030", dense.to_string());
+        assert_eq!("102\n030", sparse.to_string());
+    }
+}
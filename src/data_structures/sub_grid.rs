@@ -0,0 +1,316 @@
+use crate::data_structures::{Grid2D, GridPoint2D};
+use std::fmt::{Display, Formatter};
+
+/// A borrowed rectangle of a `Grid2D`.
+/// Points are relative to the rectangle's top left corner.
+#[derive(Debug)]
+pub struct SubGrid<'a, T> {
+    grid: &'a Grid2D<T>,
+    top_left: GridPoint2D,
+    n_rows: usize,
+    n_cols: usize,
+}
+
+/// A mutably borrowed rectangle of a `Grid2D`.
+/// Points are relative to the rectangle's top left corner.
+#[derive(Debug)]
+pub struct SubGridMut<'a, T> {
+    grid: &'a mut Grid2D<T>,
+    top_left: GridPoint2D,
+    n_rows: usize,
+    n_cols: usize,
+}
+
+fn rectangle_fits<T>(
+    grid: &Grid2D<T>,
+    top_left: GridPoint2D,
+    n_rows: usize,
+    n_cols: usize,
+) -> bool {
+    top_left.row + n_rows <= grid.n_rows() && top_left.col + n_cols <= grid.n_cols()
+}
+
+impl<T> Grid2D<T> {
+    /// Returns `None` unless the whole rectangle lies within the grid.
+    #[allow(dead_code)]
+    pub fn sub_grid(
+        &self,
+        top_left: GridPoint2D,
+        n_rows: usize,
+        n_cols: usize,
+    ) -> Option<SubGrid<'_, T>> {
+        rectangle_fits(self, top_left, n_rows, n_cols).then_some(SubGrid {
+            grid: self,
+            top_left,
+            n_rows,
+            n_cols,
+        })
+    }
+
+    /// Returns `None` unless the whole rectangle lies within the grid.
+    #[allow(dead_code)]
+    pub fn sub_grid_mut(
+        &mut self,
+        top_left: GridPoint2D,
+        n_rows: usize,
+        n_cols: usize,
+    ) -> Option<SubGridMut<'_, T>> {
+        if !rectangle_fits(self, top_left, n_rows, n_cols) {
+            return None;
+        }
+
+        Some(SubGridMut {
+            grid: self,
+            top_left,
+            n_rows,
+            n_cols,
+        })
+    }
+
+    /// Every `n_rows` x `n_cols` rectangle of the grid, in row-major order of their top left corners.
+    /// Empty if the window is larger than the grid in either dimension.
+    #[allow(dead_code)]
+    pub fn windows(&self, n_rows: usize, n_cols: usize) -> impl Iterator<Item = SubGrid<'_, T>> {
+        let row_starts = (self.n_rows() + 1).saturating_sub(n_rows);
+        let col_starts = (self.n_cols() + 1).saturating_sub(n_cols);
+
+        (0..row_starts).flat_map(move |row| {
+            (0..col_starts).map(move |col| SubGrid {
+                grid: self,
+                top_left: GridPoint2D::new(row, col),
+                n_rows,
+                n_cols,
+            })
+        })
+    }
+}
+
+#[allow(dead_code)]
+impl<'a, T> SubGrid<'a, T> {
+    pub fn n_rows(&self) -> usize {
+        self.n_rows
+    }
+
+    pub fn n_cols(&self) -> usize {
+        self.n_cols
+    }
+
+    /// Position of the top left corner within the underlying grid.
+    pub fn top_left(&self) -> GridPoint2D {
+        self.top_left
+    }
+
+    pub fn in_bounds(&self, point: GridPoint2D) -> bool {
+        point.row < self.n_rows && point.col < self.n_cols
+    }
+
+    pub fn get(&self, point: GridPoint2D) -> Option<&'a T> {
+        if self.in_bounds(point) {
+            Some(self.grid.get_unchecked(self.top_left + point))
+        } else {
+            None
+        }
+    }
+
+    pub fn row(&self, row_num: usize) -> Option<&'a [T]> {
+        if row_num < self.n_rows {
+            let row = self.grid.row_unchecked(self.top_left.row + row_num);
+            Some(&row[self.top_left.col..self.top_left.col + self.n_cols])
+        } else {
+            None
+        }
+    }
+
+    pub fn rows(&self) -> impl Iterator<Item = &'a [T]> + '_ {
+        (0..self.n_rows).map(|row_num| self.row(row_num).unwrap())
+    }
+
+    pub fn column(&self, column_num: usize) -> Option<impl Iterator<Item = &'a T> + '_> {
+        if column_num < self.n_cols {
+            Some(self.rows().map(move |row| &row[column_num]))
+        } else {
+            None
+        }
+    }
+
+    /// Cells in row-major order.
+    pub fn cells(&self) -> impl Iterator<Item = &'a T> + '_ {
+        self.rows().flatten()
+    }
+
+    pub fn to_grid(self) -> Grid2D<T>
+    where
+        T: Clone,
+    {
+        Grid2D::from(self.cells().cloned().collect(), self.n_rows, self.n_cols)
+    }
+}
+
+#[allow(dead_code)]
+impl<T> SubGridMut<'_, T> {
+    pub fn n_rows(&self) -> usize {
+        self.n_rows
+    }
+
+    pub fn n_cols(&self) -> usize {
+        self.n_cols
+    }
+
+    pub fn in_bounds(&self, point: GridPoint2D) -> bool {
+        point.row < self.n_rows && point.col < self.n_cols
+    }
+
+    /// A read-only view of the same rectangle.
+    pub fn as_sub_grid(&self) -> SubGrid<'_, T> {
+        SubGrid {
+            grid: self.grid,
+            top_left: self.top_left,
+            n_rows: self.n_rows,
+            n_cols: self.n_cols,
+        }
+    }
+
+    pub fn get(&self, point: GridPoint2D) -> Option<&T> {
+        if self.in_bounds(point) {
+            Some(self.grid.get_unchecked(self.top_left + point))
+        } else {
+            None
+        }
+    }
+
+    pub fn get_mut(&mut self, point: GridPoint2D) -> Option<&mut T> {
+        if self.in_bounds(point) {
+            Some(self.grid.get_mut_unchecked(self.top_left + point))
+        } else {
+            None
+        }
+    }
+
+    pub fn row_mut(&mut self, row_num: usize) -> Option<&mut [T]> {
+        if row_num < self.n_rows {
+            let row = self.grid.row_mut_unchecked(self.top_left.row + row_num);
+            Some(&mut row[self.top_left.col..self.top_left.col + self.n_cols])
+        } else {
+            None
+        }
+    }
+
+    pub fn fill(&mut self, value: T)
+    where
+        T: Clone,
+    {
+        for row_num in 0..self.n_rows {
+            self.row_mut(row_num).unwrap().fill(value.clone());
+        }
+    }
+
+    /// Copies `source` into this rectangle. Panics if the dimensions differ.
+    pub fn copy_from(&mut self, source: &SubGrid<'_, T>)
+    where
+        T: Clone,
+    {
+        assert_eq!(
+            (self.n_rows, self.n_cols),
+            (source.n_rows(), source.n_cols())
+        );
+
+        for (row_num, source_row) in source.rows().enumerate() {
+            self.row_mut(row_num).unwrap().clone_from_slice(source_row);
+        }
+    }
+}
+
+impl<T> Clone for SubGrid<'_, T> {
+    fn clone(&self) -> Self {
+        *self
+    }
+}
+
+impl<T> Copy for SubGrid<'_, T> {}
+
+/// Views are equal if they have the same dimensions and contents, wherever they lie.
+impl<T> PartialEq for SubGrid<'_, T>
+where
+    T: PartialEq,
+{
+    fn eq(&self, other: &Self) -> bool {
+        self.n_rows == other.n_rows
+            && self.n_cols == other.n_cols
+            && self.rows().zip(other.rows()).all(|(a, b)| a == b)
+    }
+}
+
+impl<T> Display for SubGrid<'_, T>
+where
+    T: Display,
+{
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        for (row_num, row) in self.rows().enumerate() {
+            for item in row {
+                item.fmt(f)?;
+            }
+
+            if row_num < self.n_rows - 1 {
+                writeln!(f)?;
+            }
+        }
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::parser;
+
+    fn grid(input: &str) -> Grid2D<char> {
+        parser::as_grid2d_by_char_from_str(input, Ok).unwrap()
+    }
+
+    #[test]
+    fn test_sub_grid_views() {
+        let mut grid = grid("abcd\nefgh\nijkl");
+
+        let view = grid.sub_grid(GridPoint2D::new(1, 1), 2, 3).unwrap();
+        assert_eq!("fgh\njkl", view.to_string());
+        assert_eq!(Some(&'k'), view.get(GridPoint2D::new(1, 1)));
+        assert_eq!(None, view.get(GridPoint2D::new(2, 0)));
+        assert_eq!(
+            vec!['g', 'k'],
+            view.column(1).unwrap().copied().collect::<Vec<_>>()
+        );
+        assert!(grid.sub_grid(GridPoint2D::new(1, 1), 3, 1).is_none());
+
+        let mut view = grid.sub_grid_mut(GridPoint2D::new(0, 2), 2, 2).unwrap();
+        view.fill('.');
+        *view.get_mut(GridPoint2D::new(1, 1)).unwrap() = '#';
+        assert_eq!("ab..\nef.#\nijkl", grid.to_string());
+    }
+
+    #[test]
+    fn test_windows() {
+        let grid = grid("aba\nbab\naba");
+
+        let windows: Vec<_> = grid.windows(2, 2).collect();
+        assert_eq!(4, windows.len());
+        assert_eq!(GridPoint2D::new(1, 0), windows[2].top_left());
+        assert!(windows[0] == windows[3]);
+        assert!(windows[0] != windows[1]);
+
+        assert_eq!(0, grid.windows(4, 1).count());
+    }
+
+    #[test]
+    fn test_column_mut_and_differences() {
+        let mut grid = grid("ab\ncd\nef");
+        for cell in grid.column_mut_unchecked(1).rev().take(2) {
+            *cell = '.';
+        }
+        assert_eq!("ab\nc.\ne.", grid.to_string());
+
+        assert_eq!(1, grid.row_differences(1, 2));
+        assert_eq!(0, grid.row_differences(0, 0));
+        assert_eq!(3, grid.column_differences(0, 1));
+    }
+}
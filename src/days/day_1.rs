@@ -1,6 +1,34 @@
+use crate::data_structures::PatternMatcher;
 use crate::{parser, utils, AdventErr, AdventResult};
 use std::fs::File;
 
+/// Calibration digits written out as numerals
+const NUMERALS: [(&str, u64); 10] = [
+    ("0", 0),
+    ("1", 1),
+    ("2", 2),
+    ("3", 3),
+    ("4", 4),
+    ("5", 5),
+    ("6", 6),
+    ("7", 7),
+    ("8", 8),
+    ("9", 9),
+];
+
+/// Calibration digits spelled out in English
+const ENGLISH_WORDS: [(&str, u64); 9] = [
+    ("one", 1),
+    ("two", 2),
+    ("three", 3),
+    ("four", 4),
+    ("five", 5),
+    ("six", 6),
+    ("seven", 7),
+    ("eight", 8),
+    ("nine", 9),
+];
+
 pub fn run(mut input_file: File) -> AdventResult<()> {
     let calibration_doc = parser::as_vec_by_line(&mut input_file, |s| Ok(s.to_string()))?;
 
@@ -16,102 +44,94 @@ pub fn run(mut input_file: File) -> AdventResult<()> {
 }
 
 fn part_1(calibration_lines: &[String]) -> AdventResult<()> {
-    let calibration_sum = calibration_lines
-        .iter()
-        .map(|line| calibration_value(line))
-        .sum::<AdventResult<u32>>()?;
+    let recogniser = PatternMatcher::new(NUMERALS)?;
+    let calibration_sum = sum_calibration_values(calibration_lines, &recogniser)?;
 
     println!("Sum of calibration values: {calibration_sum}");
-    return Ok(());
-
-    fn calibration_value(line: &str) -> AdventResult<u32> {
-        let first_digit = line
-            .chars()
-            .filter_map(|c| char::to_digit(c, 10))
-            .next()
-            .ok_or_else(|| {
-                AdventErr::InputParse(format!("Failed to find first digit in line:\n{line}"))
-            })?;
-
-        let last_digit = line
-            .chars()
-            .rev()
-            .filter_map(|c| char::to_digit(c, 10))
-            .next()
-            .ok_or_else(|| {
-                AdventErr::InputParse(format!("Failed to find last digit in line:\n{line}"))
-            })?;
-
-        Ok(10 * first_digit + last_digit)
-    }
+    Ok(())
 }
 
 fn part_2(calibration_lines: &[String]) -> AdventResult<()> {
-    fn calibration_value(line: &str) -> u32 {
-        const DIGIT_STRINGS: [&str; 9] = [
-            "one", "two", "three", "four", "five", "six", "seven", "eight", "nine",
-        ];
+    let recogniser = PatternMatcher::new(NUMERALS.into_iter().chain(ENGLISH_WORDS))?;
+    let calibration_sum = sum_calibration_values(calibration_lines, &recogniser)?;
 
-        // First digit
-
-        // Find actual digit
-        let (mut earliest_index, mut first_digit) = line
-            .chars()
-            .enumerate()
-            .filter_map(|(index, c)| char::to_digit(c, 10).map(|value| (index, value)))
-            .next()
-            .unwrap_or((usize::MAX, 0));
-
-        // Check words
-        for (value, digit_name) in DIGIT_STRINGS
-            .iter()
-            .enumerate()
-            .map(|(index, name)| (index + 1, name))
-        {
-            if let Some(index) = line.find(digit_name) {
-                if index < earliest_index {
-                    earliest_index = index;
-                    first_digit = value as u32;
-                }
-            }
-        }
-
-        // Last digit
-        let char_count = line.chars().count();
-
-        // Find actual digit
-        let (mut latest_index, mut last_digit) = line
-            .chars()
-            .rev()
-            .enumerate()
-            .filter_map(|(index, c)| {
-                char::to_digit(c, 10).map(|value| (char_count - index - 1, value))
-            })
-            .next()
-            .unwrap_or((0usize, 0));
-
-        // Check words
-        for (value, digit_name) in DIGIT_STRINGS
-            .iter()
-            .enumerate()
-            .map(|(index, name)| (index + 1, name))
-        {
-            if let Some(index) = line.rfind(digit_name) {
-                if index > latest_index {
-                    latest_index = index;
-                    last_digit = value as u32;
-                }
-            }
-        }
-
-        10 * first_digit + last_digit
-    }
+    println!("Sum of calibration values: {calibration_sum}");
+    Ok(())
+}
 
-    let calibration_sum: u32 = calibration_lines
+fn sum_calibration_values(
+    calibration_lines: &[String],
+    recogniser: &PatternMatcher<u64>,
+) -> AdventResult<u64> {
+    calibration_lines
         .iter()
-        .map(|line| calibration_value(line))
-        .sum();
+        .map(|line| calibration_value(line, recogniser))
+        .sum()
+}
 
-    println!("Sum of calibration values: {calibration_sum}");
-    Ok(())
+/// The first number recognised in the line, followed by the last.
+/// Numbers of more than one digit are written out in full, so "ten" and "two" make 102.
+fn calibration_value(line: &str, recogniser: &PatternMatcher<u64>) -> AdventResult<u64> {
+    let Some((first, last)) = recogniser.first_and_last(line) else {
+        return Err(AdventErr::InputParse(format!(
+            "Failed to find a digit in line:\n{line}"
+        )));
+    };
+
+    let last_digits = last.value.checked_ilog10().unwrap_or(0) + 1;
+    10_u64
+        .checked_pow(last_digits)
+        .and_then(|shift| first.value.checked_mul(shift))
+        .and_then(|value| value.checked_add(*last.value))
+        .ok_or_else(|| {
+            AdventErr::InputParse(format!("Calibration value too large in line:\n{line}"))
+        })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_overlapping_words() {
+        let recogniser = PatternMatcher::new(NUMERALS.into_iter().chain(ENGLISH_WORDS)).unwrap();
+        assert_eq!(82, calibration_value("eightwo", &recogniser).unwrap());
+        assert_eq!(83, calibration_value("xeightwothree", &recogniser).unwrap());
+        assert_eq!(77, calibration_value("seven", &recogniser).unwrap());
+        assert!(calibration_value("no digits", &recogniser).is_err());
+
+        let lines = [
+            "two1nine",
+            "eightwothree",
+            "abcone2threexyz",
+            "7pqrstsixteen",
+        ];
+        let lines = lines.map(String::from);
+        assert_eq!(
+            29 + 83 + 13 + 76,
+            sum_calibration_values(&lines, &recogniser).unwrap()
+        );
+    }
+
+    #[test]
+    fn test_multiple_digits() {
+        let recogniser = PatternMatcher::new(
+            ENGLISH_WORDS
+                .into_iter()
+                .chain([("ten", 10), ("twelve", 12)]),
+        )
+        .unwrap();
+        assert_eq!(102, calibration_value("tenxtwo", &recogniser).unwrap());
+        assert_eq!(1010, calibration_value("ten", &recogniser).unwrap());
+        assert_eq!(1012, calibration_value("tentwelve", &recogniser).unwrap());
+
+        // Too many digits to fit once put together
+        let recogniser = PatternMatcher::new([("two", 2), ("huge", u64::MAX / 10)]).unwrap();
+        assert!(calibration_value("two huge", &recogniser).is_err());
+        let recogniser = PatternMatcher::new([("one", 1), ("huge", u64::MAX)]).unwrap();
+        assert!(matches!(
+            calibration_value("one huge", &recogniser),
+            Err(AdventErr::InputParse(_))
+        ));
+    }
 }
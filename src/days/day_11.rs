@@ -1,6 +1,7 @@
-use crate::data_structures::{Grid2D, GridPoint2D};
+use crate::data_structures::{Grid2D, GridPoint2D, SparsePoint2D};
+use crate::geometry::{LinearDistance, ScalingPoint};
 use crate::AdventErr::InputParse;
-use crate::{parser, utils, AdventErr, AdventResult};
+use crate::{geometry, parser, utils, AdventErr, AdventResult};
 use std::fs::File;
 
 pub fn run(mut input_file: File) -> AdventResult<()> {
@@ -13,38 +14,35 @@ pub fn run(mut input_file: File) -> AdventResult<()> {
         Ok(element)
     })?;
 
+    let distances = geometry::sum_pairwise_manhattan_scaling(&scaling_galaxies(&galaxies, &image))?;
+
     // Part 1
     utils::part_header(1);
-    part_1(&galaxies, &image);
+    part_1(distances);
 
     // Part 2
     utils::part_header(2);
-    part_2(&galaxies, &image);
+    part_2(distances);
 
     Ok(())
 }
 
-fn part_1(galaxies: &[GridPoint2D], image: &Grid2D<Element>) {
-    let expanded_galaxies = expand_universe(galaxies, image, 2);
-
-    let pairwise_distance_sum: usize = sum_pairwise_manhattan_distances(&expanded_galaxies);
+fn part_1(distances: LinearDistance) {
+    let pairwise_distance_sum = distances.evaluate(2);
 
     println!("Sum of pairwise galaxy distances: {pairwise_distance_sum}");
 }
 
-fn part_2(galaxies: &[GridPoint2D], image: &Grid2D<Element>) {
-    let expanded_galaxies = expand_universe(galaxies, image, 1_000_000);
-
-    let pairwise_distance_sum: usize = sum_pairwise_manhattan_distances(&expanded_galaxies);
+fn part_2(distances: LinearDistance) {
+    let pairwise_distance_sum = distances.evaluate(1_000_000);
 
     println!("Sum of pairwise galaxy distances: {pairwise_distance_sum}");
 }
 
-fn expand_universe(
-    galaxies: &[GridPoint2D],
-    image: &Grid2D<Element>,
-    expansion_factor: usize,
-) -> Vec<GridPoint2D> {
+/// Positions as a function of the expansion factor.
+/// Each empty row or column before a galaxy becomes `factor` of them,
+/// so a galaxy after `n` of them is at `original - n + factor * n`.
+fn scaling_galaxies(galaxies: &[GridPoint2D], image: &Grid2D<Element>) -> Vec<ScalingPoint> {
     let mut row_empty = vec![true; image.n_rows()];
     let mut col_empty = vec![true; image.n_cols()];
 
@@ -56,13 +54,18 @@ fn expand_universe(
     let row_skips = count_true_before_or_at(&row_empty);
     let col_skips = count_true_before_or_at(&col_empty);
 
-    let mut expanded_galaxies = galaxies.to_vec();
-    for galaxy in expanded_galaxies.iter_mut() {
-        galaxy.row += row_skips[galaxy.row] * (expansion_factor - 1);
-        galaxy.col += col_skips[galaxy.col] * (expansion_factor - 1);
-    }
-
-    expanded_galaxies
+    galaxies
+        .iter()
+        .map(|galaxy| {
+            let growth =
+                SparsePoint2D::new(row_skips[galaxy.row] as i64, col_skips[galaxy.col] as i64);
+            let base = SparsePoint2D::new(
+                galaxy.row as i64 - growth.row,
+                galaxy.col as i64 - growth.col,
+            );
+            ScalingPoint::new(base, growth)
+        })
+        .collect()
 }
 
 fn count_true_before_or_at(values: &[bool]) -> Vec<usize> {
@@ -78,20 +81,6 @@ fn count_true_before_or_at(values: &[bool]) -> Vec<usize> {
         .collect()
 }
 
-fn sum_pairwise_manhattan_distances(points: &[GridPoint2D]) -> usize {
-    points
-        .iter()
-        .enumerate()
-        .map(|(i, point_1)| {
-            points
-                .iter()
-                .skip(i + 1)
-                .map(|&point_2| point_1.manhattan_distance(point_2))
-                .sum::<usize>()
-        })
-        .sum()
-}
-
 #[derive(Debug, Copy, Clone, Eq, PartialEq)]
 enum Element {
     Empty,
@@ -111,31 +111,11 @@ where
             return false;
         }
 
-        let rows_to_bottom = self.n_rows() - row_num;
-        let min_row = if row_num < rows_to_bottom {
-            0
-        } else {
-            row_num - rows_to_bottom
-        };
-
+        let reflected_rows = row_num.min(self.n_rows() - row_num);
         let mut smudges_found = 0;
-        for first_row in min_row..row_num {
-            let second_row = row_num + (row_num - first_row - 1);
-
-            if self
-                .row_unchecked(first_row)
-                .iter()
-                .zip(self.row_unchecked(second_row))
-                .any(|(value_a, value_b)| {
-                    if *value_a != *value_b {
-                        if smudges_found == smudge_count {
-                            return true;
-                        }
-                        smudges_found += 1;
-                    }
-                    false
-                })
-            {
+        for offset in 0..reflected_rows {
+            smudges_found += self.row_differences(row_num - offset - 1, row_num + offset);
+            if smudges_found > smudge_count {
                 return false;
             }
         }
@@ -148,30 +128,11 @@ where
             return false;
         }
 
-        let cols_to_edge = self.n_cols() - column_num;
-        let min_col = if column_num < cols_to_edge {
-            0
-        } else {
-            column_num - cols_to_edge
-        };
-
+        let reflected_columns = column_num.min(self.n_cols() - column_num);
         let mut smudges_found = 0;
-        for first_column in min_col..column_num {
-            let second_column = column_num + (column_num - first_column - 1);
-
-            if self
-                .column_unchecked(first_column)
-                .zip(self.column_unchecked(second_column))
-                .any(|(value_a, value_b)| {
-                    if *value_a != *value_b {
-                        if smudges_found == smudge_count {
-                            return true;
-                        }
-                        smudges_found += 1;
-                    }
-                    false
-                })
-            {
+        for offset in 0..reflected_columns {
+            smudges_found += self.column_differences(column_num - offset - 1, column_num + offset);
+            if smudges_found > smudge_count {
                 return false;
             }
         }
@@ -1,12 +1,12 @@
-use crate::data_structures::{Grid2D, GridPoint2D};
+use crate::cycle_detection::{iterate_with_cycle_detection, CycleStrategy};
+use crate::data_structures::{BitGrid, Grid2D, GridPoint2D};
 use crate::AdventErr::InputParse;
 use crate::{parser, utils, AdventErr, AdventResult};
-use std::collections::HashMap;
 use std::fmt::{Display, Formatter};
 use std::fs::File;
 
 pub fn run(mut input_file: File) -> AdventResult<()> {
-    let mut grid = parser::as_grid2d_by_char(&mut input_file, |c| Space::try_from(c))?;
+    let grid = parser::as_grid2d_by_char(&mut input_file, |c| Space::try_from(c))?;
 
     // Part 1
     utils::part_header(1);
@@ -14,7 +14,7 @@ pub fn run(mut input_file: File) -> AdventResult<()> {
 
     // Part 2
     utils::part_header(2);
-    part_2(&mut grid);
+    part_2(grid);
 
     Ok(())
 }
@@ -26,28 +26,30 @@ fn part_1(grid: &mut Grid2D<Space>) {
     println!("Load on north support beams: {north_support_load}");
 }
 
-fn part_2(grid: &mut Grid2D<Space>) {
-    const TOTAL_CYCLES: u32 = 1_000_000_000;
-    let mut cache = Some(HashMap::new());
-
-    let mut i: u32 = 0;
-    while i < TOTAL_CYCLES {
-        if cache.is_some() {
-            if let Some(&last_seen) = cache.as_ref().unwrap().get(grid) {
-                let cycle_len = i - last_seen;
-                let skip_iterations = (TOTAL_CYCLES - i) / cycle_len;
-                i += skip_iterations * cycle_len;
-                cache = None;
-            } else {
-                cache.as_mut().unwrap().insert(grid.clone(), i);
-            }
-        }
-
-        grid.spin_cycle();
-        i += 1;
-    }
-
-    let north_support_load = grid.north_support_load();
+fn part_2(grid: Grid2D<Space>) {
+    const TOTAL_CYCLES: usize = 1_000_000_000;
+
+    // Cube rocks never move, so the round rocks alone identify the state.
+    // Packed into bits, they are cheap to hash and store.
+    let round_rocks = BitGrid::from_grid(&grid, |&space| space == Space::RoundRock);
+    let mut working_grid = grid;
+    let final_round_rocks = iterate_with_cycle_detection(
+        round_rocks,
+        |round_rocks| {
+            working_grid.place_round_rocks(round_rocks);
+            working_grid.spin_cycle();
+            BitGrid::from_grid(&working_grid, |&space| space == Space::RoundRock)
+        },
+        TOTAL_CYCLES,
+        CycleStrategy::Hashing,
+    )
+    .state;
+
+    let n_rows = final_round_rocks.n_rows();
+    let north_support_load: u64 = final_round_rocks
+        .points()
+        .map(|point| (n_rows - point.row) as u64)
+        .sum();
     println!("Load on north support beams: {north_support_load}");
 }
 
@@ -96,6 +98,21 @@ impl Grid2D<Space> {
             .sum()
     }
 
+    /// Replaces the round rocks with those in `round_rocks`, leaving cube rocks in place.
+    fn place_round_rocks(&mut self, round_rocks: &BitGrid) {
+        for row in 0..self.n_rows() {
+            for (col, space) in self.row_mut_unchecked(row).iter_mut().enumerate() {
+                if *space != Space::CubeRock {
+                    *space = if round_rocks.test(GridPoint2D::new(row, col)) {
+                        Space::RoundRock
+                    } else {
+                        Space::Empty
+                    };
+                }
+            }
+        }
+    }
+
     fn spin_cycle(&mut self) {
         self.tilt_north();
         self.tilt_west();
@@ -104,98 +121,44 @@ impl Grid2D<Space> {
     }
 
     fn tilt_north(&mut self) {
-        self.perform_tilt(
-            GridPoint2D::new(0, 0),
-            |point| Some(point.next_row()),
-            |point| Some(point.next_column()),
-        )
+        for column in 0..self.n_cols() {
+            tilt_line(self.column_mut_unchecked(column));
+        }
     }
 
     fn tilt_south(&mut self) {
-        self.perform_tilt(
-            GridPoint2D::new(self.n_rows() - 1, 0),
-            |point| point.previous_row(),
-            |point| Some(point.next_column()),
-        )
+        for column in 0..self.n_cols() {
+            tilt_line(self.column_mut_unchecked(column).rev());
+        }
     }
 
     fn tilt_east(&mut self) {
-        self.perform_tilt(
-            GridPoint2D::new(0, self.n_cols() - 1),
-            |point| point.previous_column(),
-            |point| Some(point.next_row()),
-        )
+        for row in 0..self.n_rows() {
+            tilt_line(self.row_mut_unchecked(row).iter_mut().rev());
+        }
     }
 
     fn tilt_west(&mut self) {
-        self.perform_tilt(
-            GridPoint2D::new(0, 0),
-            |point| Some(point.next_column()),
-            |point| Some(point.next_row()),
-        )
+        for row in 0..self.n_rows() {
+            tilt_line(self.row_mut_unchecked(row).iter_mut());
+        }
     }
+}
 
-    fn perform_tilt(
-        &mut self,
-        stack_ptr_start: GridPoint2D,
-        advance_in_aisle: impl Fn(GridPoint2D) -> Option<GridPoint2D>,
-        to_next_aisle: impl Fn(GridPoint2D) -> Option<GridPoint2D>,
-    ) {
-        let mut aisle_start = Some(stack_ptr_start);
-
-        'aisle: while let Some(mut stack_ptr) = aisle_start {
-            if !self.in_bounds(stack_ptr) {
-                break;
-            }
-
-            aisle_start = to_next_aisle(stack_ptr);
-
-            'group: loop {
-                // Advance stack ptr until it points to empty
-                loop {
-                    match self.get(stack_ptr) {
-                        None => continue 'aisle,
-
-                        Some(Space::Empty) => break,
-
-                        _ => {
-                            if let Some(next_stack_ptr) = advance_in_aisle(stack_ptr) {
-                                stack_ptr = next_stack_ptr;
-                            } else {
-                                continue 'aisle;
-                            }
-                        }
-                    }
-                }
-
-                let Some(mut search_ptr) = advance_in_aisle(stack_ptr) else {
-                    continue 'aisle;
-                };
-
-                loop {
-                    match self.get(search_ptr) {
-                        None => continue 'aisle,
-
-                        Some(Space::CubeRock) => {
-                            stack_ptr = search_ptr;
-                            continue 'group;
-                        }
-
-                        Some(Space::RoundRock) => {
-                            *self.get_mut_unchecked(stack_ptr) = Space::RoundRock;
-                            *self.get_mut_unchecked(search_ptr) = Space::Empty;
-                            stack_ptr = advance_in_aisle(stack_ptr).unwrap();
-                        }
-
-                        Some(Space::Empty) => {}
-                    }
-
-                    if let Some(next_search_ptr) = advance_in_aisle(search_ptr) {
-                        search_ptr = next_search_ptr
-                    } else {
-                        continue 'aisle;
-                    }
-                }
+/// Rolls every round rock as far towards the start of the line as it will go.
+fn tilt_line<'a>(line: impl Iterator<Item = &'a mut Space>) {
+    let mut line: Vec<_> = line.collect();
+
+    // Where the next round rock will come to rest
+    let mut stack_index = 0;
+    for index in 0..line.len() {
+        match *line[index] {
+            Space::Empty => {}
+            Space::CubeRock => stack_index = index + 1,
+            Space::RoundRock => {
+                *line[index] = Space::Empty;
+                *line[stack_index] = Space::RoundRock;
+                stack_index += 1;
             }
         }
     }
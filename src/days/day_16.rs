@@ -1,9 +1,8 @@
-use crate::data_structures::{Direction, Grid2D, GridPoint2D};
+use crate::data_structures::{BitGrid, Direction, Grid2D, GridPoint2D};
 use crate::AdventErr::InputParse;
 use crate::{parser, utils, AdventErr, AdventResult};
 use std::cmp;
 use std::collections::VecDeque;
-use std::fmt::{Display, Formatter};
 use std::fs::File;
 
 pub fn run(mut input_file: File) -> AdventResult<()> {
@@ -63,25 +62,18 @@ fn simulate_beam(
     start_point: GridPoint2D,
     start_direction: Direction,
 ) -> usize {
-    let mut visited_grid = Grid2D::new(
-        reference_grid.n_rows(),
-        reference_grid.n_cols(),
-        VisitedTile::new(),
-    );
+    // One layer per direction of travel, indexed by `Direction as usize`
+    let mut visited: [BitGrid; 4] =
+        std::array::from_fn(|_| BitGrid::new(reference_grid.n_rows(), reference_grid.n_cols()));
     let mut process_queue = VecDeque::new();
     process_queue.push_back((start_point, start_direction));
 
     while let Some((point, direction)) = process_queue.pop_front() {
-        let Some(visited_tile) = visited_grid.get_mut(point) else {
-            continue;
-        };
-
-        if visited_tile.already_visited(direction) {
+        let visited_layer = &mut visited[direction as usize];
+        if !visited_layer.in_bounds(point) || !visited_layer.insert(point) {
             continue;
         }
 
-        visited_tile.visit(direction);
-
         match reference_grid.get_unchecked(point) {
             Tile::Empty => {
                 if let Some(next) = point.move_direction(direction) {
@@ -139,7 +131,12 @@ fn simulate_beam(
         }
     }
 
-    visited_grid.cells().filter(|tile| tile.energized()).count()
+    let [mut energized, rest @ ..] = visited;
+    for layer in &rest {
+        energized |= layer;
+    }
+
+    energized.count_ones()
 }
 
 #[derive(Debug, Copy, Clone, Eq, PartialEq)]
@@ -166,57 +163,6 @@ impl TryFrom<char> for Tile {
     }
 }
 
-#[derive(Debug, Copy, Clone)]
-struct VisitedTile {
-    left: bool,
-    right: bool,
-    up: bool,
-    down: bool,
-}
-
-impl VisitedTile {
-    fn new() -> Self {
-        Self {
-            left: false,
-            right: false,
-            up: false,
-            down: false,
-        }
-    }
-
-    fn already_visited(self, direction: Direction) -> bool {
-        match direction {
-            Direction::Up => self.up,
-            Direction::Down => self.down,
-            Direction::Left => self.left,
-            Direction::Right => self.right,
-        }
-    }
-
-    fn visit(&mut self, direction: Direction) {
-        match direction {
-            Direction::Up => self.up = true,
-            Direction::Down => self.down = true,
-            Direction::Left => self.left = true,
-            Direction::Right => self.right = true,
-        }
-    }
-
-    fn energized(self) -> bool {
-        self.left || self.right || self.up || self.down
-    }
-}
-
-impl Display for VisitedTile {
-    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
-        if self.energized() {
-            write!(f, "#")
-        } else {
-            write!(f, ".")
-        }
-    }
-}
-
 impl GridPoint2D {
     fn reflect_forward(self, direction: Direction) -> (Option<Self>, Direction) {
         let new_direction = direction.reflect_forward();
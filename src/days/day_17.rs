@@ -1,12 +1,14 @@
 use crate::data_structures::{Direction, Grid2D, GridPoint2D};
 use crate::days::day_17::Direction::{Down, Left, Right, Up};
+use crate::render::{Color, Glyph, Layer, Renderer};
 use crate::AdventErr::{Compute, InputParse};
-use crate::{parser, utils, AdventResult};
+use crate::{parser, search, utils, AdventResult};
 use std::cmp;
-use std::cmp::Ordering;
-use std::collections::BinaryHeap;
 use std::fs::File;
 
+/// Print the chosen route over the city map after each part.
+const SHOW_ROUTE: bool = false;
+
 pub fn run(mut input_file: File) -> AdventResult<()> {
     let city = parser::as_grid2d_by_char(&mut input_file, |c| {
         c.to_digit(10)
@@ -26,7 +28,7 @@ pub fn run(mut input_file: File) -> AdventResult<()> {
 }
 
 fn part_1(cost_grid: &Grid2D<u8>) -> AdventResult<()> {
-    let min_heat_loss = least_cost(
+    let route = least_cost(
         cost_grid,
         GridPoint2D::new(0, 0),
         GridPoint2D::new(cost_grid.n_rows() - 1, cost_grid.n_cols() - 1),
@@ -34,13 +36,16 @@ fn part_1(cost_grid: &Grid2D<u8>) -> AdventResult<()> {
         3,
     )?;
 
-    println!("Minimum heat loss: {min_heat_loss}");
+    println!("Minimum heat loss: {}", route.heat_loss);
+    if SHOW_ROUTE {
+        route.print_over(cost_grid)?;
+    }
 
     Ok(())
 }
 
 fn part_2(cost_grid: &Grid2D<u8>) -> AdventResult<()> {
-    let min_heat_loss = least_cost(
+    let route = least_cost(
         cost_grid,
         GridPoint2D::new(0, 0),
         GridPoint2D::new(cost_grid.n_rows() - 1, cost_grid.n_cols() - 1),
@@ -48,7 +53,10 @@ fn part_2(cost_grid: &Grid2D<u8>) -> AdventResult<()> {
         10,
     )?;
 
-    println!("Minimum heat loss: {min_heat_loss}");
+    println!("Minimum heat loss: {}", route.heat_loss);
+    if SHOW_ROUTE {
+        route.print_over(cost_grid)?;
+    }
 
     Ok(())
 }
@@ -59,84 +67,80 @@ fn least_cost(
     destination: GridPoint2D,
     min_steps_before_turn_or_stop: usize,
     max_steps_in_line: usize,
-) -> AdventResult<u64> {
+) -> AdventResult<Route> {
     assert!(entry_cost.n_rows() > 0 && entry_cost.n_cols() > 0);
     if start == destination {
-        return Ok(0);
+        return Ok(Route {
+            heat_loss: 0,
+            steps: vec![],
+        });
     }
 
     let min_steps_before_turn_or_stop = cmp::max(min_steps_before_turn_or_stop, 1);
     let max_steps_in_line = cmp::max(max_steps_in_line, 1);
 
-    let mut search_grid = Grid2D::new(entry_cost.n_rows(), entry_cost.n_cols(), VisitedCell::new());
-    *search_grid.get_mut_unchecked(start) = VisitedCell {
-        to_up: CopyRange::new(0, usize::MAX),
-        to_down: CopyRange::new(0, usize::MAX),
-        to_left: CopyRange::new(0, usize::MAX),
-        to_right: CopyRange::new(0, usize::MAX),
+    // Number every crucible state, so the search can keep its bookkeeping in vectors.
+    let states = CrucibleStates {
+        n_cols: entry_cost.n_cols(),
+        max_steps_in_line,
     };
 
-    let mut queue = BinaryHeap::new();
+    // The crucible may set off in any direction, so start facing each of them with no steps taken.
+    let starts = Direction::ALL.map(|direction| Crucible::new(start, direction, 0));
+
+    let successors = |crucible: &Crucible| {
+        let try_move = |direction, continuous_steps| {
+            let point = crucible.point.move_direction(direction)?;
+            let &cost = entry_cost.get(point)?;
+            Some((
+                Crucible::new(point, direction, continuous_steps),
+                cost as u64,
+            ))
+        };
 
-    let mut init_direction = |direction: Direction| {
-        if let Some(point) = start.move_direction(direction) {
-            if let Some(&cost) = entry_cost.get(point) {
-                queue.push(SearchElement::new(direction, 1, point, cost as u64))
-            }
-        }
+        let can_turn = crucible.continuous_steps >= min_steps_before_turn_or_stop;
+        [
+            (crucible.continuous_steps < max_steps_in_line)
+                .then(|| try_move(crucible.direction, crucible.continuous_steps + 1))
+                .flatten(),
+            can_turn
+                .then(|| try_move(crucible.direction.turn_left(), 1))
+                .flatten(),
+            can_turn
+                .then(|| try_move(crucible.direction.turn_right(), 1))
+                .flatten(),
+        ]
+        .into_iter()
+        .flatten()
     };
 
-    for direction in [Up, Down, Left, Right] {
-        init_direction(direction);
-    }
-
-    let worth_exploring = |seen_range: CopyRange<usize>, steps_so_far| {
-        steps_so_far < seen_range.start
-            || (steps_so_far > seen_range.end && seen_range.end < max_steps_in_line - 1)
+    let is_goal = |crucible: &Crucible| {
+        crucible.point == destination && crucible.continuous_steps >= min_steps_before_turn_or_stop
     };
 
-    while let Some(current_element) = queue.pop() {
-        if current_element.point == destination
-            && current_element.continuous_steps >= min_steps_before_turn_or_stop
-        {
-            return Ok(current_element.cost);
-        }
+    let result = search::dijkstra_indexed(
+        entry_cost.len() * states.per_point(),
+        |&crucible| states.index(crucible),
+        starts,
+        successors,
+        is_goal,
+    );
+    let (Some(goal), Some(heat_loss)) = (result.goal, result.goal_cost()) else {
+        return Err(Compute(format!(
+            "Failed to find any path from {start} to {destination}"
+        )));
+    };
 
-        let mut explore_direction = |direction, steps_so_far_this_direction| {
-            let visited = search_grid.get_mut_unchecked(current_element.point);
-            if !worth_exploring(visited.get(direction), steps_so_far_this_direction) {
-                return;
-            }
-
-            visited.mark_visited(direction, steps_so_far_this_direction);
-            let Some(next_point) = current_element.point.move_direction(direction) else {
-                return;
-            };
-
-            let Some(&next_cost) = entry_cost.get(next_point) else {
-                return;
-            };
-
-            queue.push(SearchElement::new(
-                direction,
-                steps_so_far_this_direction + 1,
-                next_point,
-                current_element.cost + next_cost as u64,
-            ));
-        };
+    // The first state is the crucible sitting at the start, before it has moved.
+    let steps = result
+        .path_to(&goal)
+        .unwrap()
+        .into_iter()
+        .skip(1)
+        .map(|crucible| (crucible.point, crucible.direction))
+        .collect();
 
-        if current_element.continuous_steps < max_steps_in_line {
-            explore_direction(current_element.direction, current_element.continuous_steps);
-        }
-        if current_element.continuous_steps >= min_steps_before_turn_or_stop {
-            explore_direction(current_element.direction.turn_left(), 0);
-            explore_direction(current_element.direction.turn_right(), 0);
-        }
-    }
-
-    Err(Compute(format!(
-        "Failed to find any path from {start} to {destination}"
-    )))
+    Ok(Route { heat_loss, steps })
 }
 
 impl Direction {
@@ -160,84 +164,149 @@ impl Direction {
 }
 
 #[derive(Debug, Copy, Clone, Eq, PartialEq, Hash)]
-struct CopyRange<T: Copy> {
-    start: T,
-    end: T,
+struct Crucible {
+    point: GridPoint2D,
+    direction: Direction,
+    continuous_steps: usize,
 }
 
-impl<T: Copy> CopyRange<T> {
-    fn new(start: T, end: T) -> Self {
-        Self { start, end }
+impl Crucible {
+    fn new(point: GridPoint2D, direction: Direction, continuous_steps: usize) -> Self {
+        Self {
+            point,
+            direction,
+            continuous_steps,
+        }
     }
 }
 
+/// Numbers each crucible state on a grid, by point, then direction, then steps taken.
 #[derive(Debug, Copy, Clone)]
-struct VisitedCell {
-    to_up: CopyRange<usize>,
-    to_down: CopyRange<usize>,
-    to_left: CopyRange<usize>,
-    to_right: CopyRange<usize>,
+struct CrucibleStates {
+    n_cols: usize,
+    max_steps_in_line: usize,
 }
 
-impl VisitedCell {
-    fn new() -> Self {
-        Self {
-            to_up: CopyRange::new(usize::MAX, 0),
-            to_down: CopyRange::new(usize::MAX, 0),
-            to_left: CopyRange::new(usize::MAX, 0),
-            to_right: CopyRange::new(usize::MAX, 0),
-        }
+impl CrucibleStates {
+    fn per_point(self) -> usize {
+        4 * (self.max_steps_in_line + 1)
     }
 
-    fn get(self, direction: Direction) -> CopyRange<usize> {
-        match direction {
-            Up => self.to_up,
-            Down => self.to_down,
-            Left => self.to_left,
-            Right => self.to_right,
-        }
+    fn index(self, crucible: Crucible) -> usize {
+        let point = crucible.point.row * self.n_cols + crucible.point.col;
+        (point * 4 + crucible.direction as usize) * (self.max_steps_in_line + 1)
+            + crucible.continuous_steps
     }
+}
 
-    fn mark_visited(&mut self, direction: Direction, steps_so_far_this_direction: usize) {
-        let range = match direction {
-            Up => &mut self.to_up,
-            Down => &mut self.to_down,
-            Left => &mut self.to_left,
-            Right => &mut self.to_right,
-        };
+/// Each step is the point entered, along with the direction moved to enter it.
+#[derive(Debug, Clone)]
+struct Route {
+    heat_loss: u64,
+    steps: Vec<(GridPoint2D, Direction)>,
+}
 
-        range.start = cmp::min(range.start, steps_so_far_this_direction);
-        range.end = cmp::max(range.end, steps_so_far_this_direction);
+impl Route {
+    /// Prints the route as arrows over the heat loss digits of the city.
+    fn print_over(&self, city: &Grid2D<u8>) -> AdventResult<()> {
+        Renderer::new(city, |&heat_loss| {
+            Glyph::plain(char::from_digit(heat_loss as u32, 10).unwrap_or('?'))
+        })
+        .with_layer(Layer::route(&self.steps, Color::Red))
+        .print()?;
+
+        Ok(())
     }
 }
 
-#[derive(Debug, Copy, Clone, Eq, PartialEq)]
-struct SearchElement {
-    direction: Direction,
-    continuous_steps: usize,
-    point: GridPoint2D,
-    cost: u64,
-}
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const EXAMPLE: &str = "2413432311323
+3215453535623
+3255245654254
+3446585845452
+4546657867536
+1438598798454
+4457876987766
+3637877979653
+4654967986887
+4564679986453
+1224686865563
+2546548887735
+4322674655533";
+
+    fn parse_city(input: &str) -> Grid2D<u8> {
+        let rows: Vec<_> = input.lines().collect();
+        let values = rows
+            .iter()
+            .flat_map(|row| row.chars().map(|c| c.to_digit(10).unwrap() as u8))
+            .collect();
+        Grid2D::from(values, rows.len(), rows[0].len())
+    }
 
-impl SearchElement {
-    fn new(direction: Direction, continuous_steps: usize, point: GridPoint2D, cost: u64) -> Self {
-        Self {
-            direction,
-            continuous_steps,
-            point,
-            cost,
+    /// Checks the route is a connected path across the city, costing its heat loss,
+    /// and returns the lengths of its straight runs.
+    fn check_route(city: &Grid2D<u8>, route: &Route) -> Vec<usize> {
+        let mut current = GridPoint2D::new(0, 0);
+        let mut heat_loss = 0;
+        for &(point, direction) in &route.steps {
+            assert_eq!(Some(point), current.move_direction(direction));
+            heat_loss += *city.get(point).unwrap() as u64;
+            current = point;
         }
+
+        assert_eq!(
+            GridPoint2D::new(city.n_rows() - 1, city.n_cols() - 1),
+            current
+        );
+        assert_eq!(route.heat_loss, heat_loss);
+
+        route
+            .steps
+            .chunk_by(|(_, a), (_, b)| a == b)
+            .map(<[_]>::len)
+            .collect()
     }
-}
 
-impl PartialOrd for SearchElement {
-    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
-        other.cost.partial_cmp(&self.cost)
+    fn corner_to_corner(city: &Grid2D<u8>, min_steps: usize, max_steps: usize) -> Route {
+        least_cost(
+            city,
+            GridPoint2D::new(0, 0),
+            GridPoint2D::new(city.n_rows() - 1, city.n_cols() - 1),
+            min_steps,
+            max_steps,
+        )
+        .unwrap()
+    }
+
+    #[test]
+    fn test_crucible_route() {
+        let city = parse_city(EXAMPLE);
+        let route = corner_to_corner(&city, 0, 3);
+
+        assert_eq!(102, route.heat_loss);
+        assert!(check_route(&city, &route).iter().all(|&run| run <= 3));
     }
-}
 
-impl Ord for SearchElement {
-    fn cmp(&self, other: &Self) -> Ordering {
-        other.cost.cmp(&self.cost)
+    #[test]
+    fn test_ultra_crucible_route() {
+        let city = parse_city(EXAMPLE);
+        let route = corner_to_corner(&city, 4, 10);
+
+        assert_eq!(94, route.heat_loss);
+        assert!(check_route(&city, &route)
+            .iter()
+            .all(|run| (4..=10).contains(run)));
+
+        // The ultra crucible cannot stop until it has gone four in a line.
+        let unlucky =
+            parse_city("111111111111\n999999999991\n999999999991\n999999999991\n999999999991");
+        let route = corner_to_corner(&unlucky, 4, 10);
+        assert_eq!(71, route.heat_loss);
+        assert!(check_route(&unlucky, &route)
+            .iter()
+            .all(|run| (4..=10).contains(run)));
     }
 }
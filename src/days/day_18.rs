@@ -1,4 +1,5 @@
 use crate::data_structures::Direction;
+use crate::geometry::LatticePolygon;
 use crate::AdventErr::Compute;
 use crate::{parser, utils, AdventResult};
 use std::cmp::{max, min, Ordering};
@@ -28,6 +29,19 @@ pub fn run(mut input_file: File) -> AdventResult<()> {
 fn run_part(instructions: &[Instruction]) -> AdventResult<()> {
     let trench_size = trench_size(instructions)?;
 
+    // The trench is a lattice polygon, so Pick's theorem gives the same count.
+    let polygon = LatticePolygon::from_instructions(
+        instructions
+            .iter()
+            .map(|instruction| (instruction.direction, instruction.length)),
+    )?;
+    let enclosed_points = polygon.enclosed_points()?;
+    if enclosed_points != trench_size as u128 {
+        return Err(Compute(format!(
+            "Sweep found {trench_size} cells, but Pick's theorem gives {enclosed_points}"
+        )));
+    }
+
     println!("Trench size: {trench_size}");
 
     Ok(())
@@ -1,4 +1,6 @@
-use crate::days::day_19::types::{CopyRange, Destination, Part, PartRange, SortResult, Workflow};
+use crate::days::day_19::types::{
+    split_by_rule, Destination, Part, PartRange, SortResult, Workflow,
+};
 use crate::AdventErr::Compute;
 use crate::{utils, AdventResult};
 use std::fs::File;
@@ -7,16 +9,9 @@ mod parsing;
 mod types;
 
 pub fn run(mut input_file: File) -> AdventResult<()> {
-    let (workflows, parts) = parsing::parse_input(&mut input_file)?;
+    let (workflows, parts, n_categories) = parsing::parse_input(&mut input_file)?;
 
-    let start_index = workflows
-        .iter()
-        .position(|workflow| workflow.name == START_WORKFLOW_NAME)
-        .ok_or_else(|| {
-            Compute(format!(
-                "Unable to find start workflow '{START_WORKFLOW_NAME}'"
-            ))
-        })?;
+    let start_index = start_workflow_index(&workflows)?;
 
     // Part 1
     utils::part_header(1);
@@ -24,41 +19,81 @@ pub fn run(mut input_file: File) -> AdventResult<()> {
 
     // Part 2
     utils::part_header(2);
-    part_2(&workflows, start_index);
+    part_2(&workflows, start_index, n_categories)?;
 
     Ok(())
 }
 
 const START_WORKFLOW_NAME: &str = "in";
+const MIN_RATING: i64 = 1;
+const MAX_RATING: i64 = 4000;
 
-fn part_1(workflows: &[Workflow], parts: &[Part], start_workflow_index: usize) -> AdventResult<()> {
-    let accepted_part_rating_sum: u64 = parts
+fn start_workflow_index(workflows: &[Workflow]) -> AdventResult<usize> {
+    workflows
         .iter()
-        .map(|&part| {
-            sort_part(part, workflows, start_workflow_index).map(|result| match result {
-                SortResult::Accepted => part.rating_sum(),
-                SortResult::Rejected => 0,
-            })
+        .position(|workflow| workflow.name == START_WORKFLOW_NAME)
+        .ok_or_else(|| {
+            Compute(format!(
+                "Unable to find start workflow '{START_WORKFLOW_NAME}'"
+            ))
         })
-        .sum::<AdventResult<_>>()?;
+}
+
+fn part_1(workflows: &[Workflow], parts: &[Part], start_workflow_index: usize) -> AdventResult<()> {
+    let accepted_part_rating_sum = accepted_rating_sum(workflows, parts, start_workflow_index)?;
 
     println!("Sum of ratings of accepted parts: {accepted_part_rating_sum}");
 
     Ok(())
 }
 
-fn part_2(workflows: &[Workflow], start_workflow_index: usize) {
-    let acceptable_ratings = total_acceptable_ratings(
-        workflows,
-        start_workflow_index,
-        PartRange::new(CopyRange::new(1, 4001)),
-    );
+fn part_2(
+    workflows: &[Workflow],
+    start_workflow_index: usize,
+    n_categories: usize,
+) -> AdventResult<()> {
+    let acceptable_ratings =
+        acceptable_combinations(workflows, start_workflow_index, n_categories)?;
 
     println!("Acceptable combinations of ratings: {acceptable_ratings}");
+
+    Ok(())
+}
+
+fn accepted_rating_sum(
+    workflows: &[Workflow],
+    parts: &[Part],
+    start_workflow_index: usize,
+) -> AdventResult<i64> {
+    parts
+        .iter()
+        .map(|part| {
+            sort_part(part, workflows, start_workflow_index).map(|result| match result {
+                SortResult::Accepted => part.rating_sum(),
+                SortResult::Rejected => 0,
+            })
+        })
+        .sum()
+}
+
+/// How many combinations of ratings from `MIN_RATING` to `MAX_RATING` are accepted.
+fn acceptable_combinations(
+    workflows: &[Workflow],
+    start_workflow_index: usize,
+    n_categories: usize,
+) -> AdventResult<u128> {
+    let all_parts = PartRange::new(
+        vec![MIN_RATING; n_categories],
+        vec![MAX_RATING + 1; n_categories],
+    )
+    .ok_or_else(|| Compute(String::from("Empty range of ratings")))?;
+
+    total_acceptable_ratings(workflows, start_workflow_index, all_parts)
+        .ok_or_else(|| Compute(String::from("Too many combinations of ratings to count")))
 }
 
 fn sort_part(
-    part: Part,
+    part: &Part,
     workflows: &[Workflow],
     mut workflow_index: usize,
 ) -> AdventResult<SortResult> {
@@ -71,28 +106,115 @@ fn sort_part(
     })
 }
 
+/// `None` if the total overflows.
 fn total_acceptable_ratings(
     workflows: &[Workflow],
     workflow_index: usize,
     mut current_part: PartRange,
-) -> u64 {
-    let mut total = 0;
+) -> Option<u128> {
+    let mut total: u128 = 0;
     for &rule in &workflows[workflow_index].rules {
-        if let Some((deeper_part, destination)) = current_part.apply_rule(rule) {
+        let (matching, rest) = split_by_rule(current_part, rule);
+        if let Some((deeper_part, destination)) = matching {
             match destination {
-                Destination::Accept => total += deeper_part.size(),
+                Destination::Accept => total = total.checked_add(deeper_part.checked_volume()?)?,
                 Destination::Reject => { /* Do nothing */ }
                 Destination::Workflow(next) => {
-                    total += total_acceptable_ratings(workflows, next, deeper_part)
+                    total = total.checked_add(total_acceptable_ratings(
+                        workflows,
+                        next,
+                        deeper_part,
+                    )?)?
                 }
             }
         }
 
-        let Some(next_part) = current_part.apply_rule_inverse(rule) else {
+        let Some(next_part) = rest else {
             break;
         };
         current_part = next_part;
     }
 
-    total
+    Some(total)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const EXAMPLE: &str = "px{a<2006:qkq,m>2090:A,rfg}
+pv{a>1716:R,A}
+lnx{m>1548:A,A}
+rfg{s<537:gd,x>2440:R,A}
+qs{s>3448:A,lnx}
+qkq{x<1416:A,crn}
+crn{x>2662:A,R}
+in{s<1351:px,qqz}
+qqz{s>2770:qs,m<1801:hdj,R}
+gd{a>3333:R,R}
+hdj{m>838:A,pv}
+
+{x=787,m=2655,a=1222,s=2876}
+{x=1679,m=44,a=2067,s=496}
+{x=2036,m=264,a=79,s=2244}
+{x=2461,m=1339,a=466,s=291}
+{x=2127,m=1623,a=2188,s=1013}";
+
+    /// Five categories, none of them the puzzle's, with two first seen in the parts
+    const OTHER_CATEGORIES: &str = "in{red>10:A,blue<5:R,green>2:A,R}
+
+{red=11,blue=1,green=1,gold=1,sky=1}
+{sky=1,gold=1,green=3,blue=4,red=1}
+{red=1,blue=6,green=3,gold=2,sky=2}
+{red=1,blue=6,green=1,gold=1,sky=1}";
+
+    fn solve(input: &str) -> (usize, i64, u128) {
+        let (workflows, parts, n_categories) = parsing::parse_text(input).unwrap();
+        let start = start_workflow_index(&workflows).unwrap();
+        (
+            n_categories,
+            accepted_rating_sum(&workflows, &parts, start).unwrap(),
+            acceptable_combinations(&workflows, start, n_categories).unwrap(),
+        )
+    }
+
+    #[test]
+    fn test_example() {
+        assert_eq!((4, 19114, 167409079868000), solve(EXAMPLE));
+    }
+
+    #[test]
+    fn test_other_categories() {
+        let (n_categories, rating_sum, combinations) = solve(OTHER_CATEGORIES);
+        assert_eq!(5, n_categories);
+        assert_eq!(15 + 14, rating_sum);
+
+        // Either red is over 10, or blue is at least 5 and green over 2.
+        let each = MAX_RATING as u128;
+        assert_eq!(
+            3990 * each.pow(4) + 10 * 3996 * 3998 * each.pow(2),
+            combinations
+        );
+    }
+
+    #[test]
+    fn test_many_categories() {
+        let input = |n_categories: usize| {
+            let ratings: Vec<_> = (0..n_categories)
+                .map(|category| format!("{}=1", "abcdefghijkl".chars().nth(category).unwrap()))
+                .collect();
+            format!("in{{A}}\n\n{{{}}}", ratings.join(","))
+        };
+
+        let each = MAX_RATING as u128;
+        assert_eq!((10, 10, each.pow(10)), solve(&input(10)));
+
+        // Every combination is accepted, but there are too many to count
+        let (workflows, _, n_categories) = parsing::parse_text(&input(11)).unwrap();
+        let start = start_workflow_index(&workflows).unwrap();
+        assert!(matches!(
+            acceptable_combinations(&workflows, start, n_categories),
+            Err(Compute(message)) if message == "Too many combinations of ratings to count"
+        ));
+    }
 }
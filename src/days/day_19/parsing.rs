@@ -1,36 +1,51 @@
-use crate::days::day_19::types::{Destination, Part, Rule, Workflow};
+use crate::days::day_19::types::{Category, Destination, Part, Rule, Workflow};
 use crate::AdventErr::InputParse;
-use crate::{parser, AdventResult};
+use crate::AdventResult;
 use lazy_static::lazy_static;
 use regex::Regex;
 use std::collections::HashMap;
 use std::fs::File;
 use std::io::Read;
 
-pub(super) fn parse_input(input_file: &mut File) -> AdventResult<(Vec<Workflow>, Vec<Part>)> {
+/// Also returns the number of rating categories found.
+pub(super) fn parse_input(
+    input_file: &mut File,
+) -> AdventResult<(Vec<Workflow>, Vec<Part>, usize)> {
     let mut input = String::new();
     input_file.read_to_string(&mut input)?;
 
+    parse_text(&input)
+}
+
+/// As `parse_input`, from the text of the input.
+pub(super) fn parse_text(input: &str) -> AdventResult<(Vec<Workflow>, Vec<Part>, usize)> {
     let Some((workflows, parts)) = input.split_once("\n\n") else {
         return Err(InputParse(String::from(
             "Failed to split input into sections",
         )));
     };
 
-    Ok((parse_workflows(workflows)?, parse_parts(parts)?))
+    let mut name_to_category = HashMap::new();
+    let workflows = parse_workflows(workflows, &mut name_to_category)?;
+    let parts = parse_parts(parts, &mut name_to_category)?;
+
+    Ok((workflows, parts, name_to_category.len()))
 }
 
 lazy_static! {
     static ref WORKFLOW_OUTER_REGEX: Regex =
         Regex::new(r"(?<name>[A-Za-z]+)\{(?<rules>.+)\}").unwrap();
     static ref RULE_REGEX: Regex = Regex::new(
-        r"(?<category>[xmas])(?<comparison>[<>])(?<value>\d+):(?<destination>[A-Za-z]+)"
+        r"(?<category>[a-z]+)(?<comparison>[<>])(?<value>\d+):(?<destination>[A-Za-z]+)"
     )
     .unwrap();
     static ref DESTINATION_REGEX: Regex = Regex::new("^[A-Za-z]+$").unwrap();
 }
 
-fn parse_workflows(section: &str) -> AdventResult<Vec<Workflow>> {
+fn parse_workflows<'a>(
+    section: &'a str,
+    name_to_category: &mut HashMap<&'a str, Category>,
+) -> AdventResult<Vec<Workflow>> {
     let mut name_to_index = HashMap::new();
     let mut workflows = Vec::new();
 
@@ -46,7 +61,8 @@ fn parse_workflows(section: &str) -> AdventResult<Vec<Workflow>> {
         let rules = caps.name("rules").unwrap().as_str();
         for rule in rules.split(',') {
             let rule = if let Some(caps) = RULE_REGEX.captures(rule) {
-                let category = caps["category"].chars().next().unwrap().try_into()?;
+                let category =
+                    get_category(caps.name("category").unwrap().as_str(), name_to_category);
                 let comparison = caps["comparison"].chars().next().unwrap();
                 let value = caps["value"]
                     .parse()
@@ -101,30 +117,91 @@ fn get_workflow_index<'a, 'b: 'a>(
     })
 }
 
+/// Categories are numbered in the order they are first seen.
+fn get_category<'a>(name: &'a str, name_to_category: &mut HashMap<&'a str, Category>) -> Category {
+    let next_category = name_to_category.len();
+    *name_to_category.entry(name).or_insert(next_category)
+}
+
 lazy_static! {
-    static ref PART_REGEX: Regex =
-        Regex::new(r"\{x=(?<x>\d+),m=(?<m>\d+),a=(?<a>\d+),s=(?<s>\d+)\}").unwrap();
+    static ref PART_REGEX: Regex = Regex::new(r"^\{(?<ratings>.*)\}$").unwrap();
+    static ref RATING_REGEX: Regex = Regex::new(r"^(?<category>[a-z]+)=(?<value>\d+)$").unwrap();
 }
 
-fn parse_parts(section: &str) -> AdventResult<Vec<Part>> {
-    parser::as_vec_by_line_from_str(section, |line| {
+/// Every part must give a rating for every category, including those only seen in later parts.
+fn parse_parts<'a>(
+    section: &'a str,
+    name_to_category: &mut HashMap<&'a str, Category>,
+) -> AdventResult<Vec<Part>> {
+    // Ratings by category, until every category is known
+    let mut parts_ratings = vec![];
+    for line in section.lines() {
         let Some(caps) = PART_REGEX.captures(line) else {
             return Err(InputParse(format!("Failed to match part line:\n{line}")));
         };
 
-        let x = caps["x"]
-            .parse()
-            .map_err(|_| InputParse(format!("Failed to parse x value from part '{line}'")))?;
-        let m = caps["m"]
-            .parse()
-            .map_err(|_| InputParse(format!("Failed to parse m value from part '{line}'")))?;
-        let a = caps["a"]
-            .parse()
-            .map_err(|_| InputParse(format!("Failed to parse a value from part '{line}'")))?;
-        let s = caps["s"]
-            .parse()
-            .map_err(|_| InputParse(format!("Failed to parse s value from part '{line}'")))?;
-
-        Ok(Part { x, m, a, s })
-    })
+        let mut ratings = vec![None; name_to_category.len()];
+        for rating in caps.name("ratings").unwrap().as_str().split(',') {
+            let Some(caps) = RATING_REGEX.captures(rating) else {
+                return Err(InputParse(format!(
+                    "Failed to parse rating '{rating}' from part '{line}'"
+                )));
+            };
+
+            let category = get_category(caps.name("category").unwrap().as_str(), name_to_category);
+            let value = caps["value"]
+                .parse()
+                .map_err(|_| InputParse(format!("Failed to parse value from part '{line}'")))?;
+
+            if category >= ratings.len() {
+                ratings.resize(category + 1, None);
+            }
+            if ratings[category].replace(value).is_some() {
+                return Err(InputParse(format!(
+                    "Repeated rating category in part '{line}'"
+                )));
+            }
+        }
+
+        parts_ratings.push(ratings);
+    }
+
+    let n_categories = name_to_category.len();
+    parts_ratings
+        .into_iter()
+        .map(|mut ratings| {
+            ratings.resize(n_categories, None);
+            let ratings = ratings.into_iter().collect::<Option<_>>().ok_or_else(|| {
+                InputParse(String::from(
+                    "Some parts are missing ratings for some categories",
+                ))
+            })?;
+            Ok(Part { ratings })
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_many_categories() {
+        let names = ["a", "b", "c", "d", "e", "f", "g", "h", "i", "j", "k", "l"];
+        let mut name_to_category = HashMap::new();
+        for (expected, name) in names.iter().enumerate() {
+            assert_eq!(expected, get_category(name, &mut name_to_category));
+        }
+        assert_eq!(2, get_category("c", &mut name_to_category));
+
+        let ratings: Vec<_> = names.iter().map(|name| format!("{name}=1")).collect();
+        let input = format!("in{{a>1:A,R}}\n\n{{{}}}", ratings.join(","));
+        let (_, parts, n_categories) = parse_text(&input).unwrap();
+        assert_eq!(names.len(), n_categories);
+        assert_eq!(vec![1; names.len()], parts[0].ratings);
+
+        // Categories first seen in a later part must be rated by earlier ones too
+        assert!(parse_text("in{a>1:A,R}\n\n{a=1}\n{a=1,b=2}").is_err());
+        assert!(parse_text("in{a>1:A,R}\n\n{a=1,a=2}").is_err());
+    }
 }
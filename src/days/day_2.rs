@@ -1,45 +1,141 @@
 use crate::{parser, utils, AdventErr, AdventResult};
 use lazy_static::lazy_static;
 use regex::Regex;
-use std::cmp::max;
+use std::collections::{BTreeMap, BTreeSet};
+use std::fmt::{Display, Formatter};
 use std::fs::File;
 
+/// Print the bags of the same size as the part 1 bag which make the most games possible.
+const SHOW_BEST_BAGS: bool = false;
+
+pub fn run(input_file: File) -> AdventResult<()> {
+    // The bag the Elf asks about in part 1
+    run_with_bag(
+        input_file,
+        &CubeGroup::from_counts([("red", 12), ("green", 13), ("blue", 14)]),
+    )
+}
+
 //noinspection DuplicatedCode
-pub fn run(mut input_file: File) -> AdventResult<()> {
+fn run_with_bag(mut input_file: File, bag: &CubeGroup) -> AdventResult<()> {
     let games = parser::as_vec_by_line(&mut input_file, line_parser)?;
 
+    let colors = all_colors(&games);
+
     // Part 1
     utils::part_header(1);
-    part_1(&games)?;
+    part_1(&games, bag, &colors)?;
 
     // Part 2
     utils::part_header(2);
-    part_2(&games)?;
+    part_2(&games, &colors)?;
 
     Ok(())
 }
 
-fn part_1(games: &[CubeGameInstance]) -> AdventResult<()> {
-    let valid_game_sum: usize = games
-        .iter()
-        .filter(|game| {
-            let most_seen = game.most_seen();
-            most_seen.red <= 12 && most_seen.green <= 13 && most_seen.blue <= 14
-        })
-        .map(|game| game.id)
-        .sum();
+fn part_1(games: &[CubeGameInstance], bag: &CubeGroup, colors: &[String]) -> AdventResult<()> {
+    let valid_game_sum: usize = possible_games(games, bag).map(|game| game.id).sum();
 
     println!("Sum of possible games: {valid_game_sum}");
+
+    if SHOW_BEST_BAGS {
+        let total = bag.total();
+        let most_possible = best_bags_of_size(games, colors, total, |best_bag| {
+            println!("{best_bag}");
+        });
+        println!("Most games possible with {total} cubes, by the bags above: {most_possible}");
+    }
+
     Ok(())
 }
 
-fn part_2(games: &[CubeGameInstance]) -> AdventResult<()> {
-    let game_power_sum: u32 = games.iter().map(|game| game.most_seen().power()).sum();
+fn part_2(games: &[CubeGameInstance], colors: &[String]) -> AdventResult<()> {
+    let game_power_sum: u64 = games
+        .iter()
+        .map(|game| game.minimum_bag().power(colors))
+        .sum();
 
     println!("Sum of game powers: {game_power_sum}");
     Ok(())
 }
 
+/// Every color seen in any game, in alphabetical order.
+fn all_colors(games: &[CubeGameInstance]) -> Vec<String> {
+    games
+        .iter()
+        .flat_map(|game| &game.reveals)
+        .flat_map(|reveal| reveal.counts.keys().cloned())
+        .collect::<BTreeSet<_>>()
+        .into_iter()
+        .collect()
+}
+
+/// The games which could have been played with `bag`.
+fn possible_games<'a>(
+    games: &'a [CubeGameInstance],
+    bag: &'a CubeGroup,
+) -> impl Iterator<Item = &'a CubeGameInstance> {
+    games.iter().filter(|game| game.minimum_bag().fits_in(bag))
+}
+
+/// Finds the bags holding exactly `total` cubes of the given colors which make the most games possible,
+/// calling `visit` with each of them, and returns how many games they make possible.
+/// Every such bag is tried, of which there are `(total + k - 1) choose (k - 1)` for `k` colors,
+/// once to find the most possible and again to visit the best, as there may be nearly as many of those.
+fn best_bags_of_size(
+    games: &[CubeGameInstance],
+    colors: &[String],
+    total: u32,
+    mut visit: impl FnMut(&CubeGroup),
+) -> usize {
+    let minimum_bags: Vec<_> = games.iter().map(|game| game.minimum_bag()).collect();
+    let possible = |bag: &CubeGroup| {
+        minimum_bags
+            .iter()
+            .filter(|minimum| minimum.fits_in(bag))
+            .count()
+    };
+
+    let mut most_possible = 0;
+    for_each_bag(colors, total, &mut CubeGroup::empty(), &mut |bag| {
+        most_possible = most_possible.max(possible(bag));
+    });
+
+    for_each_bag(colors, total, &mut CubeGroup::empty(), &mut |bag| {
+        if possible(bag) == most_possible {
+            visit(bag);
+        }
+    });
+
+    most_possible
+}
+
+/// Calls `visit` with every bag of exactly `remaining` cubes split between the colors.
+fn for_each_bag(
+    colors: &[String],
+    remaining: u32,
+    bag: &mut CubeGroup,
+    visit: &mut impl FnMut(&CubeGroup),
+) {
+    match colors {
+        [] => {
+            if remaining == 0 {
+                visit(bag);
+            }
+        }
+        [last] => {
+            bag.counts.insert(last.clone(), remaining);
+            visit(bag);
+        }
+        [color, rest @ ..] => {
+            for count in 0..=remaining {
+                bag.counts.insert(color.clone(), count);
+                for_each_bag(rest, remaining - count, bag, visit);
+            }
+        }
+    }
+}
+
 #[derive(Debug)]
 struct CubeGameInstance {
     id: usize,
@@ -47,35 +143,77 @@ struct CubeGameInstance {
 }
 
 impl CubeGameInstance {
-    fn most_seen(&self) -> CubeGroup {
-        self.reveals
-            .iter()
-            .fold(CubeGroup::empty(), |acc, view| CubeGroup {
-                red: max(acc.red, view.red),
-                green: max(acc.green, view.green),
-                blue: max(acc.blue, view.blue),
-            })
+    /// The fewest cubes of each color the bag could have held.
+    fn minimum_bag(&self) -> CubeGroup {
+        let mut result = CubeGroup::empty();
+        for reveal in &self.reveals {
+            for (color, &count) in &reveal.counts {
+                let most_seen = result.counts.entry(color.clone()).or_default();
+                *most_seen = (*most_seen).max(count);
+            }
+        }
+
+        result
     }
 }
 
-#[derive(Debug, Copy, Clone)]
+/// A number of cubes of each color. Colors not present have no cubes.
+#[derive(Debug, Clone, Eq, PartialEq)]
 struct CubeGroup {
-    red: u32,
-    green: u32,
-    blue: u32,
+    counts: BTreeMap<String, u32>,
 }
 
 impl CubeGroup {
     fn empty() -> Self {
         Self {
-            red: 0,
-            green: 0,
-            blue: 0,
+            counts: BTreeMap::new(),
+        }
+    }
+
+    fn from_counts<'a>(counts: impl IntoIterator<Item = (&'a str, u32)>) -> Self {
+        Self {
+            counts: counts
+                .into_iter()
+                .map(|(color, count)| (color.to_string(), count))
+                .collect(),
         }
     }
 
-    fn power(&self) -> u32 {
-        self.red * self.green * self.blue
+    fn count(&self, color: &str) -> u32 {
+        self.counts.get(color).copied().unwrap_or(0)
+    }
+
+    fn total(&self) -> u32 {
+        self.counts.values().sum()
+    }
+
+    /// Whether every cube here could be drawn from `bag`.
+    fn fits_in(&self, bag: &CubeGroup) -> bool {
+        self.counts
+            .iter()
+            .all(|(color, &count)| count <= bag.count(color))
+    }
+
+    /// The product of the counts of the given colors.
+    fn power(&self, colors: &[String]) -> u64 {
+        colors
+            .iter()
+            .map(|color| self.count(color) as u64)
+            .product()
+    }
+}
+
+/// e.g. `12 red, 13 green`
+impl Display for CubeGroup {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        for (index, (color, count)) in self.counts.iter().enumerate() {
+            if index > 0 {
+                write!(f, ", ")?;
+            }
+            write!(f, "{count} {color}")?;
+        }
+
+        Ok(())
     }
 }
 
@@ -111,16 +249,12 @@ fn line_parser(line: &str) -> AdventResult<CubeGameInstance> {
                     )));
                 };
 
-                match &caps["color"] {
-                    "red" => cube_group.red = count,
-                    "green" => cube_group.green = count,
-                    "blue" => cube_group.blue = count,
-                    s => {
-                        return Err(AdventErr::InputParse(format!(
-                            "Unknown color '{s}' in game {id}. Line:\n{line}"
-                        )))
-                    }
-                };
+                let color = &caps["color"];
+                if cube_group.counts.insert(color.to_string(), count).is_some() {
+                    return Err(AdventErr::InputParse(format!(
+                        "Color '{color}' repeated in a reveal in game {id}. Line:\n{line}"
+                    )));
+                }
             }
 
             Ok(cube_group)
@@ -129,3 +263,109 @@ fn line_parser(line: &str) -> AdventResult<CubeGameInstance> {
 
     Ok(CubeGameInstance { id, reveals })
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const EXAMPLE: &str = "Game 1: 3 blue, 4 red; 1 red, 2 green, 6 blue; 2 green
+Game 2: 1 blue, 2 green; 3 green, 4 blue, 1 red; 1 green, 1 blue
+Game 3: 8 green, 6 blue, 20 red; 5 blue, 4 red, 13 green; 5 green, 1 red
+Game 4: 1 green, 3 red, 6 blue; 3 green, 6 red; 3 green, 15 blue, 14 red
+Game 5: 6 red, 1 blue, 3 green; 2 blue, 1 red, 2 green";
+
+    const FOUR_COLORS: &str = "Game 1: 1 red, 2 green; 3 blue, 4 yellow
+Game 2: 5 yellow, 1 red, 1 green, 1 blue";
+
+    fn games(input: &str) -> Vec<CubeGameInstance> {
+        input
+            .lines()
+            .map(|line| line_parser(line).unwrap())
+            .collect()
+    }
+
+    fn ids<'a>(games: impl Iterator<Item = &'a CubeGameInstance>) -> Vec<usize> {
+        games.map(|game| game.id).collect()
+    }
+
+    fn best_bags(games: &[CubeGameInstance], total: u32) -> (usize, Vec<CubeGroup>) {
+        let mut best = vec![];
+        let most_possible = best_bags_of_size(games, &all_colors(games), total, |bag| {
+            best.push(bag.clone())
+        });
+        (most_possible, best)
+    }
+
+    #[test]
+    fn test_example() {
+        let games = games(EXAMPLE);
+        let colors = all_colors(&games);
+        assert_eq!(vec!["blue", "green", "red"], colors);
+
+        let bag = CubeGroup::from_counts([("red", 12), ("green", 13), ("blue", 14)]);
+        assert_eq!(vec![1, 2, 5], ids(possible_games(&games, &bag)));
+
+        assert_eq!(
+            CubeGroup::from_counts([("red", 20), ("green", 13), ("blue", 6)]),
+            games[2].minimum_bag()
+        );
+        let powers: Vec<_> = games
+            .iter()
+            .map(|game| game.minimum_bag().power(&colors))
+            .collect();
+        assert_eq!(vec![48, 12, 1560, 630, 36], powers);
+        assert_eq!(2286, powers.iter().sum::<u64>());
+    }
+
+    #[test]
+    fn test_best_bags() {
+        // No bag of 39 holds enough for both games 3 and 4. Leaving out game 3 leaves
+        // 7 spare cubes to share between 3 colors, and leaving out game 4 leaves none.
+        let (most_possible, best) = best_bags(&games(EXAMPLE), 39);
+        assert_eq!(4, most_possible);
+        assert_eq!(36 + 1, best.len());
+        assert!(best.contains(&CubeGroup::from_counts([
+            ("blue", 6),
+            ("green", 13),
+            ("red", 20)
+        ])));
+        assert!(best.contains(&CubeGroup::from_counts([
+            ("blue", 15),
+            ("green", 3),
+            ("red", 21)
+        ])));
+    }
+
+    #[test]
+    fn test_other_colors() {
+        let games = games(FOUR_COLORS);
+        let colors = all_colors(&games);
+        assert_eq!(vec!["blue", "green", "red", "yellow"], colors);
+        assert_eq!(4, games[0].minimum_bag().count("yellow"));
+        assert_eq!(0, games[0].minimum_bag().count("purple"));
+
+        let powers: Vec<_> = games
+            .iter()
+            .map(|game| game.minimum_bag().power(&colors))
+            .collect();
+        assert_eq!(vec![24, 5], powers);
+
+        let rgb_bag = CubeGroup::from_counts([("red", 12), ("green", 13), ("blue", 14)]);
+        assert!(ids(possible_games(&games, &rgb_bag)).is_empty());
+        let bag = CubeGroup::from_counts([("red", 1), ("green", 2), ("blue", 3), ("yellow", 4)]);
+        assert_eq!(vec![1], ids(possible_games(&games, &bag)));
+
+        // One bag fits game 1 exactly. Game 2 leaves 2 spare cubes to share between 4 colors.
+        let (most_possible, best) = best_bags(&games, 10);
+        assert_eq!(1, most_possible);
+        assert_eq!(1 + 10, best.len());
+        assert!(best.contains(&CubeGroup::from_counts([
+            ("blue", 3),
+            ("green", 2),
+            ("red", 1),
+            ("yellow", 4)
+        ])));
+
+        assert!(line_parser("Game 3: 1 red, 2 red").is_err());
+    }
+}
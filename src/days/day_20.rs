@@ -50,18 +50,15 @@ fn part_2(modules: &[Module], broadcast_index: usize) -> AdventResult<()> {
         .into_iter()
         .unzip();
 
-    let base = math::chinese_remainder_theorem(&offsets, &cycles).ok_or_else(|| {
+    let congruence = math::chinese_remainder_theorem(&offsets, &cycles)?.ok_or_else(|| {
         Compute(String::from(
             "Failed to compute solution for set of counters",
         ))
     })?;
-    let min_value = *offsets.iter().max().unwrap(); // If no values were present, the above line would have returned.
 
-    let first_finish_pulse = if base > min_value {
-        base
-    } else {
-        base + math::lcm(&cycles).unwrap()
-    };
+    // The press must come after every counter's offset.
+    let min_value = offsets.iter().copied().max().unwrap_or(0);
+    let first_finish_pulse = congruence.first_at_least(min_value as u128 + 1)?;
 
     println!("Button presses required for low pulse to rx: {first_finish_pulse}");
 
@@ -1,4 +1,5 @@
 use crate::data_structures::{Grid2D, GridPoint2D};
+use crate::days::day_21::implementation::InfiniteGarden;
 use crate::AdventErr::{Compute, InputParse};
 use crate::{parser, utils, AdventResult};
 use std::fs::File;
@@ -75,8 +76,8 @@ fn part_2(grid: &Grid2D<Tile>, starting_position: GridPoint2D) -> AdventResult<(
     }
 
     const PART_2_STEPS: u64 = 26501365;
-    let reachable_plots =
-        implementation::count_visitable_infinite(grid, starting_position, PART_2_STEPS);
+    let garden = InfiniteGarden::new(grid, starting_position);
+    let reachable_plots = garden.count_visitable_by_recurrence(PART_2_STEPS)?;
 
     println!("Reachable garden plots in {PART_2_STEPS} steps: {reachable_plots}");
 
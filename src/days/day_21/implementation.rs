@@ -1,5 +1,8 @@
-use crate::data_structures::{Grid2D, GridPoint2D};
+use crate::data_structures::{BitGrid, Grid2D, GridPoint2D};
 use crate::days::day_21::Tile;
+use crate::math::Matrix;
+use crate::AdventErr::Compute;
+use crate::AdventResult;
 use std::collections::VecDeque;
 
 pub fn count_visitable_finite(
@@ -7,58 +10,149 @@ pub fn count_visitable_finite(
     starting_position: GridPoint2D,
     total_steps: u64,
 ) -> u64 {
-    let steps_grid = create_step_grid(reference_grid, starting_position);
+    let gardens = BitGrid::from_grid(reference_grid, |&tile| tile == Tile::Garden);
+
+    // Plots reachable after exactly `steps - 1` and `steps - 2` steps.
+    // Once a step reproduces the set from two steps earlier, the sets alternate forever.
+    let mut reachable = BitGrid::new(reference_grid.n_rows(), reference_grid.n_cols());
+    reachable.set(starting_position);
+    let mut previous = reachable.clone();
+    previous.clear_all();
+
+    for steps in 1..=total_steps {
+        let mut next = reachable.neighbours();
+        next &= &gardens;
+
+        if next == previous {
+            return if (total_steps - steps).is_multiple_of(2) {
+                next.count_ones() as u64
+            } else {
+                reachable.count_ones() as u64
+            };
+        }
 
-    steps_grid
-        .cells()
-        .filter(|&&x| match x {
-            Some(steps_required) => {
-                steps_required <= total_steps && steps_required % 2 == total_steps % 2
-            }
-            None => false,
-        })
-        .count() as u64
+        previous = reachable;
+        reachable = next;
+    }
+
+    reachable.count_ones() as u64
 }
 
-pub fn count_visitable_infinite(
-    reference_grid: &Grid2D<Tile>,
+/// The grid repeated infinitely in every direction, along with the steps needed to reach
+/// each plot of the instances in each quarter, which do not depend on how many steps are taken.
+/// Building these once lets counts for several numbers of steps share them.
+pub struct InfiniteGarden<'a> {
+    reference_grid: &'a Grid2D<Tile>,
     starting_position: GridPoint2D,
-    total_steps: u64,
-) -> u64 {
-    // Count how many cells are visitable within the starting instance
-    let mut total_visitable =
-        count_visitable_finite(reference_grid, starting_position, total_steps);
+    /// The lower right quarter, then each other quarter rotated into its place
+    quarters: Vec<Quarter>,
+}
+
+struct Quarter {
+    grid: Grid2D<Tile>,
+    /// Steps within an instance on the horizontal axis, entering from the left
+    axis_step_grid: Grid2D<Option<u64>>,
+    /// Steps within an instance below the axis, entering from the top left corner
+    quadrant_step_grid: Grid2D<Option<u64>>,
+}
+
+impl Quarter {
+    fn new(grid: Grid2D<Tile>, starting_position: GridPoint2D) -> Self {
+        Self {
+            axis_step_grid: create_step_grid(&grid, GridPoint2D::new(starting_position.row, 0)),
+            quadrant_step_grid: create_step_grid(&grid, GridPoint2D::new(0, 0)),
+            grid,
+        }
+    }
+}
+
+impl<'a> InfiniteGarden<'a> {
+    pub fn new(reference_grid: &'a Grid2D<Tile>, starting_position: GridPoint2D) -> Self {
+        // Rotate 3 times, for the remaining quarters
+        let mut quarters = vec![Quarter::new(reference_grid.clone(), starting_position)];
+        for _ in 0..3 {
+            let rotated_grid = quarters.last().unwrap().grid.rotate_counterclockwise();
+            quarters.push(Quarter::new(rotated_grid, starting_position));
+        }
+
+        Self {
+            reference_grid,
+            starting_position,
+            quarters,
+        }
+    }
+
+    pub fn count_visitable(&self, total_steps: u64) -> u64 {
+        // Count how many cells are visitable within the starting instance
+        let mut total_visitable =
+            count_visitable_finite(self.reference_grid, self.starting_position, total_steps);
 
-    // Now count how many are visitable in one quarter (lower right)
-    total_visitable += count_visitable_quarter(reference_grid, starting_position, total_steps);
+        // Now count how many are visitable in each quarter
+        for quarter in &self.quarters {
+            total_visitable +=
+                count_visitable_quarter(quarter, self.starting_position, total_steps);
+        }
 
-    // Now rotate 3 times, compute the remaining quarter for each
-    let rotated_grid = reference_grid.rotate_counterclockwise();
-    total_visitable += count_visitable_quarter(&rotated_grid, starting_position, total_steps);
+        total_visitable
+    }
 
-    let rotated_grid = rotated_grid.rotate_counterclockwise();
-    total_visitable += count_visitable_quarter(&rotated_grid, starting_position, total_steps);
+    /// Counts by extrapolating the counts after a few grid widths of steps,
+    /// rather than counting across every instance out to `total_steps`.
+    /// With the border and the starting row and column clear, each further grid width of steps
+    /// grows the reachable diamond by a ring of instances, so the count grows quadratically.
+    /// Any quadratic `f` satisfies `f(k + 3) = 3 f(k + 2) - 3 f(k + 1) + f(k)`,
+    /// so the count after any number of widths is a power of that recurrence's matrix
+    /// applied to three samples, taking logarithmically many steps.
+    /// A fourth sample confirms the recurrence holds.
+    /// Fails rather than overflowing if the count is too large.
+    pub fn count_visitable_by_recurrence(&self, total_steps: u64) -> AdventResult<u64> {
+        // Far enough out that every instance crossed is reached in full, as the direct count expects.
+        const FIRST_SAMPLE_WIDTHS: u64 = 4;
+        const SAMPLES: u64 = 4;
+
+        let size = self.reference_grid.n_rows() as u64;
+        let remainder = total_steps % size;
+        let widths = total_steps / size;
+        if widths < FIRST_SAMPLE_WIDTHS + SAMPLES {
+            return Ok(self.count_visitable(total_steps));
+        }
 
-    let rotated_grid = rotated_grid.rotate_counterclockwise();
-    total_visitable += count_visitable_quarter(&rotated_grid, starting_position, total_steps);
+        let samples: Vec<_> = (FIRST_SAMPLE_WIDTHS..FIRST_SAMPLE_WIDTHS + SAMPLES)
+            .map(|k| self.count_visitable(remainder + k * size) as i128)
+            .collect();
+
+        // Takes [f(k + 2), f(k + 1), f(k)] to [f(k + 3), f(k + 2), f(k + 1)]
+        let step = Matrix::from_rows(vec![vec![3, -3, 1], vec![1, 0, 0], vec![0, 1, 0]])?;
+        let initial = [samples[2], samples[1], samples[0]];
+        if step.mul_vector(&initial)[0] != samples[3] {
+            return Err(Compute(format!(
+                "Reachable plots do not grow quadratically: {samples:?}"
+            )));
+        }
 
-    total_visitable
+        let count = step
+            .checked_pow(widths - FIRST_SAMPLE_WIDTHS)
+            .and_then(|power| power.checked_mul_vector(&initial))
+            .ok_or_else(|| Compute(format!("Plot count overflows after {widths} grid widths")))?[2];
+        u64::try_from(count).map_err(|_| Compute(format!("Plot count out of range: {count}")))
+    }
 }
 
 fn count_visitable_quarter(
-    reference_grid: &Grid2D<Tile>,
+    quarter: &Quarter,
     starting_position: GridPoint2D,
     total_steps: u64,
 ) -> u64 {
-    count_visitable_horiz_axis_right(reference_grid, starting_position, total_steps)
-        + count_visitable_fourth_quadrant(reference_grid, starting_position, total_steps)
+    count_visitable_horiz_axis_right(quarter, starting_position, total_steps)
+        + count_visitable_fourth_quadrant(quarter, starting_position, total_steps)
 }
 
 fn count_visitable_fourth_quadrant(
-    reference_grid: &Grid2D<Tile>,
+    quarter: &Quarter,
     starting_position: GridPoint2D,
     total_steps: u64,
 ) -> u64 {
+    let reference_grid = &quarter.grid;
     // Below positive horizontal axis (not including tiles directly along vertical axis)
 
     let steps_to_first_instance = ((reference_grid.n_cols() - starting_position.col)
@@ -70,12 +164,12 @@ fn count_visitable_fourth_quadrant(
     }
 
     let steps_on_axis_right = total_steps - steps_to_first_instance;
-    // Generate the step grid when entering from the top left corner.
-    let step_grid = create_step_grid(reference_grid, GridPoint2D::new(0, 0));
-    let visitable = count_visitable_with_skipping(&step_grid, steps_on_axis_right);
+    // Use the step grid when entering from the top left corner.
+    let step_grid = &quarter.quadrant_step_grid;
+    let visitable = count_visitable_with_skipping(step_grid, steps_on_axis_right);
 
     // Compute how many even/odd parity spots there are.
-    let (full_grid_even_count, full_grid_odd_count) = count_parity_cells(&step_grid);
+    let (full_grid_even_count, full_grid_odd_count) = count_parity_cells(step_grid);
 
     // Sum up multiple rows of the skipped instances.
     // This can be solved via triangular numbers.
@@ -126,10 +220,11 @@ fn count_visitable_fourth_quadrant(
 }
 
 fn count_visitable_horiz_axis_right(
-    reference_grid: &Grid2D<Tile>,
+    quarter: &Quarter,
     starting_position: GridPoint2D,
     total_steps: u64,
 ) -> u64 {
+    let reference_grid = &quarter.grid;
     // First, let's consume the number of steps required to get to the first instance to the right.
     let steps_to_first_instance = (reference_grid.n_cols() - starting_position.col) as u64;
     if steps_to_first_instance > total_steps {
@@ -138,12 +233,12 @@ fn count_visitable_horiz_axis_right(
     }
 
     let steps_on_axis_right = total_steps - steps_to_first_instance;
-    // Let's generate the step grid when entering from this point - we'll need it.
-    let step_grid = create_step_grid(reference_grid, GridPoint2D::new(starting_position.row, 0));
-    let visitable = count_visitable_with_skipping(&step_grid, steps_on_axis_right);
+    // Use the step grid when entering from this point.
+    let step_grid = &quarter.axis_step_grid;
+    let visitable = count_visitable_with_skipping(step_grid, steps_on_axis_right);
 
     // Compute how many even/odd parity spots there are.
-    let (full_grid_even_count, full_grid_odd_count) = count_parity_cells(&step_grid);
+    let (full_grid_even_count, full_grid_odd_count) = count_parity_cells(step_grid);
 
     visitable.skipped_even_instances * full_grid_even_count
         + visitable.skipped_odd_instances * full_grid_odd_count
@@ -325,3 +420,148 @@ fn create_step_grid(
 
     steps_grid
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::math::Polynomial;
+
+    /// Counts by extrapolating from a tiled copy of the grid, as a check on `InfiniteGarden`.
+    /// Too slow to run alongside it on every solve.
+    /// With the border and the starting row and column clear, each further grid width of steps
+    /// grows the reachable diamond by a ring of instances, so the count grows quadratically.
+    /// The quadratic is fitted to the counts after the first few widths by Newton forward differences,
+    /// and a fourth sample confirms it is no more than quadratic.
+    fn count_visitable_by_growth_fit(
+        reference_grid: &Grid2D<Tile>,
+        starting_position: GridPoint2D,
+        total_steps: u64,
+    ) -> AdventResult<u64> {
+        const SAMPLES: usize = 4;
+
+        let size = reference_grid.n_rows();
+        let remainder = total_steps % size as u64;
+        let widths = total_steps / size as u64;
+
+        // Enough copies each way to hold the furthest sample.
+        let radius = SAMPLES;
+        let tiled = tile(reference_grid, 2 * radius + 1);
+        let tiled_start = GridPoint2D::new(
+            starting_position.row + radius * size,
+            starting_position.col + radius * size,
+        );
+
+        let samples: Vec<_> = (0..SAMPLES as u64)
+            .map(|k| {
+                count_visitable_finite(&tiled, tiled_start, remainder + k * size as u64) as i64
+            })
+            .collect();
+
+        let growth = Polynomial::newton_forward(&samples)?;
+        if growth.degree().is_some_and(|degree| degree > 2) {
+            return Err(Compute(format!(
+                "Reachable plots do not grow quadratically: {samples:?}"
+            )));
+        }
+
+        let widths = i64::try_from(widths)
+            .map_err(|_| Compute(format!("Too many grid widths to fit: {widths}")))?;
+        let count = growth.evaluate_integer(widths)?;
+        u64::try_from(count).map_err(|_| Compute(format!("Plot count out of range: {count}")))
+    }
+
+    /// `copies` x `copies` instances of the grid, side by side.
+    fn tile<T: Copy>(reference_grid: &Grid2D<T>, copies: usize) -> Grid2D<T> {
+        let n_rows = reference_grid.n_rows() * copies;
+        let n_cols = reference_grid.n_cols() * copies;
+
+        let mut cells = Vec::with_capacity(n_rows * n_cols);
+        for row in 0..n_rows {
+            let source_row = reference_grid.row_unchecked(row % reference_grid.n_rows());
+            for _ in 0..copies {
+                cells.extend_from_slice(source_row);
+            }
+        }
+
+        Grid2D::from(cells, n_rows, n_cols)
+    }
+
+    /// Clear around the border and along the starting row and column, as the puzzle input is.
+    const GARDEN: &str = "...........
+.#.#.......
+.......#...
+..#.....#..
+....#......
+...........
+.......#...
+..#.....#..
+...#.......
+......#..#.
+...........";
+
+    fn garden() -> (Grid2D<Tile>, GridPoint2D) {
+        let rows: Vec<_> = GARDEN.lines().collect();
+        let tiles = rows
+            .iter()
+            .flat_map(|row| row.chars())
+            .map(|c| if c == '#' { Tile::Rock } else { Tile::Garden })
+            .collect();
+        let size = rows.len();
+        (
+            Grid2D::from(tiles, size, size),
+            GridPoint2D::new(size / 2, size / 2),
+        )
+    }
+
+    #[test]
+    fn test_infinite_against_tiled() {
+        let (grid, start) = garden();
+        let copies = 23;
+        let tiled = tile(&grid, copies);
+        let tiled_start = GridPoint2D::new(
+            start.row + copies / 2 * grid.n_rows(),
+            start.col + copies / 2 * grid.n_cols(),
+        );
+
+        // At least three grid widths, as the puzzle's steps run to many thousands of them
+        for steps in [33, 38, 49, 60, 93, 116] {
+            assert_eq!(
+                count_visitable_finite(&tiled, tiled_start, steps),
+                InfiniteGarden::new(&grid, start).count_visitable(steps),
+                "{steps} steps"
+            );
+        }
+    }
+
+    #[test]
+    fn test_infinite_against_growth_fit() {
+        let (grid, start) = garden();
+        for steps in [5 + 11 * 20, 5 + 11 * 1001, 2 + 11 * 500, 26501365] {
+            assert_eq!(
+                count_visitable_by_growth_fit(&grid, start, steps).unwrap(),
+                InfiniteGarden::new(&grid, start).count_visitable(steps),
+                "{steps} steps"
+            );
+        }
+
+        assert!(count_visitable_by_growth_fit(&grid, start, u64::MAX).is_err());
+    }
+
+    #[test]
+    fn test_infinite_against_recurrence() {
+        let (grid, start) = garden();
+        for steps in [5 + 11 * 20, 5 + 11 * 1001, 2 + 11 * 500, 26501365, 40] {
+            assert_eq!(
+                InfiniteGarden::new(&grid, start)
+                    .count_visitable_by_recurrence(steps)
+                    .unwrap(),
+                InfiniteGarden::new(&grid, start).count_visitable(steps),
+                "{steps} steps"
+            );
+        }
+
+        assert!(InfiniteGarden::new(&grid, start)
+            .count_visitable_by_recurrence(u64::MAX)
+            .is_err());
+    }
+}
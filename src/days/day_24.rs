@@ -1,24 +1,16 @@
 use crate::data_structures::Grid2D;
+use crate::geometry::{Ray3, Vector3, XyIntersection};
+use crate::math::{LinearSolution, Rational};
 use crate::AdventErr::{Compute, InputParse};
-use crate::{math, parser, utils, AdventErr, AdventResult};
+use crate::{math, parser, utils, AdventResult};
 use lazy_static::lazy_static;
+use num::{BigRational, ToPrimitive};
 use regex::Regex;
-use std::fmt::{Display, Formatter};
 use std::fs::File;
 use std::ops::RangeInclusive;
-use std::str::FromStr;
 
 pub fn run(mut input_file: File) -> AdventResult<()> {
-    let hailstones = parser::as_vec_by_line(&mut input_file, |line| {
-        let Some((position, velocity)) = line.split_once('@') else {
-            return Err(InputParse(format!("Failed to split line:\n{line}")));
-        };
-
-        Ok(Parametric {
-            position: Point3D::from_str(position)?,
-            velocity: Point3D::from_str(velocity)?,
-        })
-    })?;
+    let hailstones = parser::as_vec_by_line(&mut input_file, parse_hailstone)?;
 
     // Part 1
     utils::part_header(1);
@@ -31,183 +23,270 @@ pub fn run(mut input_file: File) -> AdventResult<()> {
     Ok(())
 }
 
-fn part_1(hailstones: &[Parametric]) -> AdventResult<()> {
-    let slope_intercepts: Vec<_> = hailstones
-        .iter()
-        .map(|parametric| parametric.to_x_y_slope_intercept())
-        .collect::<AdventResult<_>>()?;
+const TEST_AREA_MIN: i128 = 200000000000000;
+const TEST_AREA_MAX: i128 = 400000000000000;
 
-    const MIN_BOUND: f64 = 200000000000000_f64;
-    const MAX_BOUND: f64 = 400000000000000_f64;
-    const ACCEPTABLE_RANGE: RangeInclusive<f64> = MIN_BOUND..=MAX_BOUND;
-
-    let path_intersections: usize = slope_intercepts
-        .iter()
-        .enumerate()
-        .map(|(stone_a, slope_intercept_a)| {
-            slope_intercepts
-                .iter()
-                .enumerate()
-                .skip(stone_a + 1)
-                .filter(|(stone_b, slope_intercept_b)| {
-                    let Some((x, y)) = slope_intercept_a.intersection(**slope_intercept_b) else {
-                        return false;
-                    };
-
-                    if !ACCEPTABLE_RANGE.contains(&x) || !ACCEPTABLE_RANGE.contains(&y) {
-                        return false;
-                    }
-
-                    hailstones[stone_a].time_for_x(x) >= 0_f64
-                        && hailstones[*stone_b].time_for_x(x) >= 0_f64
-                })
-                .count()
-        })
-        .sum();
+fn part_1(hailstones: &[Hailstone]) -> AdventResult<()> {
+    let path_intersections = count_path_crossings(hailstones, &(TEST_AREA_MIN..=TEST_AREA_MAX))?;
 
     println!("Intersections within test area: {path_intersections}");
 
     Ok(())
 }
 
-/// This solution is based on a bunch of algebra done by-hand.
-/// It wasn't fun, and I don't suggest you try following it,
-/// if you're reading this in the future.
-fn part_2(hailstones: &[Parametric]) -> AdventResult<()> {
-    if hailstones.len() < 5 {
-        return Err(Compute(String::from(
-            "At least 5 pieces of data required to solve",
-        )));
+/// Counts the pairs of hailstones whose future paths meet in the xy plane,
+/// somewhere inside the square `test_area` in both x and y.
+fn count_path_crossings(
+    hailstones: &[Hailstone],
+    test_area: &RangeInclusive<i128>,
+) -> AdventResult<usize> {
+    let mut count = 0;
+    for (index, stone_a) in hailstones.iter().enumerate() {
+        for stone_b in &hailstones[index + 1..] {
+            if paths_cross_in_area(stone_a, stone_b, test_area)? {
+                count += 1;
+            }
+        }
     }
 
-    let coefficients: Vec<_> = hailstones[..5]
-        .iter()
-        .map(|data| ExpressionCoefficients {
-            a: -data.position.y,
-            b: data.position.x,
-            c: data.velocity.y,
-            d: -data.velocity.x,
-            g: data.position.y * data.velocity.x - data.position.x * data.velocity.y,
-        })
-        .collect();
+    Ok(count)
+}
 
-    let mut a_values = Grid2D::new(4, 4, 0_f64);
-    let mut b_values = [0_f64; 4];
-    for row_num in 0..4 {
-        let e1 = coefficients[row_num];
-        let e2 = coefficients[row_num + 1];
+fn paths_cross_in_area(
+    stone_a: &Hailstone,
+    stone_b: &Hailstone,
+    test_area: &RangeInclusive<i128>,
+) -> AdventResult<bool> {
+    let times = match stone_a.line().xy_intersection(&stone_b.line()) {
+        XyIntersection::Crossing { time, other_time } => {
+            if time < Rational::ZERO || other_time < Rational::ZERO {
+                return Ok(false);
+            }
+            TimeInterval::single(time)
+        }
 
-        let row = a_values.row_mut_unchecked(row_num);
-        row[0] = (e1.a - e2.a) as f64;
-        row[1] = (e1.b - e2.b) as f64;
-        row[2] = (e1.c - e2.c) as f64;
-        row[3] = (e1.d - e2.d) as f64;
-        b_values[row_num] = (e2.g - e1.g) as f64;
-    }
+        XyIntersection::Parallel => return Ok(false),
+
+        XyIntersection::Coincident => {
+            // Both paths run along the same line. Find the times at which stone a passes
+            // through points stone b also passes through, which is when b's own time is positive.
+            // Stone b is at time s0 + k t at the point stone a reaches at time t.
+            let b_speed_squared =
+                Rational::from_integer(stone_b.direction.dot_xy(stone_b.direction));
+            let s0 =
+                Rational::from_integer((stone_a.origin - stone_b.origin).dot_xy(stone_b.direction))
+                    / b_speed_squared;
+            let k = Rational::from_integer(stone_a.direction.dot_xy(stone_b.direction))
+                / b_speed_squared;
+
+            let crossover = -s0 / k;
+            let times = TimeInterval::from(Rational::ZERO);
+            if k > Rational::ZERO {
+                times.at_least(crossover)
+            } else {
+                times.at_most(crossover)
+            }
+        }
 
-    math::gauss_jordan(&mut a_values, &mut b_values)?;
+        XyIntersection::Degenerate => {
+            return Err(Compute(format!(
+                "Hailstone moving only in z cannot cross paths in the xy plane: {} or {}",
+                stone_a.direction, stone_b.direction
+            )))
+        }
+    };
 
-    let v_x = b_values[0];
-    let x = b_values[2];
-    let y = b_values[3];
+    Ok(times
+        .within(stone_a.origin.x, stone_a.direction.x, test_area)
+        .within(stone_a.origin.y, stone_a.direction.y, test_area)
+        .is_some())
+}
 
-    // Compute time for first hailstone to be hit
-    let t0 = (hailstones[0].position.x as f64 - x) / (v_x - hailstones[0].velocity.x as f64);
-    // And second
-    let t1 = (hailstones[1].position.x as f64 - x) / (v_x - hailstones[1].velocity.x as f64);
-    // From this, we can quickly compute z
-    let z_intersect_0 = hailstones[0].position.z as f64 + t0 * hailstones[0].velocity.z as f64;
-    let z_intersect_1 = hailstones[1].position.z as f64 + t1 * hailstones[1].velocity.z as f64;
-    let v_z = (z_intersect_1 - z_intersect_0) / (t1 - t0);
-    let z = (z_intersect_0) - v_z * t0;
+/// A closed interval of times. `None` once no times remain.
+#[derive(Debug, Copy, Clone)]
+struct TimeInterval(Option<(Rational, Option<Rational>)>);
 
-    let x = x.round() as i64;
-    let y = y.round() as i64;
-    let z = z.round() as i64;
+impl TimeInterval {
+    fn single(time: Rational) -> Self {
+        Self(Some((time, Some(time))))
+    }
 
-    println!("Sum of rock initial position components: {}", x + y + z);
+    /// Every time from `start` onwards
+    fn from(start: Rational) -> Self {
+        Self(Some((start, None)))
+    }
 
-    Ok(())
-}
+    fn at_least(self, time: Rational) -> Self {
+        let Some((start, end)) = self.0 else {
+            return self;
+        };
 
-#[derive(Debug, Copy, Clone)]
-struct Parametric {
-    position: Point3D,
-    velocity: Point3D,
-}
+        let start = start.max(time);
+        Self(end.is_none_or(|end| start <= end).then_some((start, end)))
+    }
 
-impl Parametric {
-    fn to_x_y_slope_intercept(self) -> AdventResult<SlopeIntercept> {
-        if self.velocity.x == 0 {
-            return Err(Compute(String::from(
-                "x velocity is 0 - cannot compute y-x slope-intercept",
-            )));
+    fn at_most(self, time: Rational) -> Self {
+        let Some((start, end)) = self.0 else {
+            return self;
+        };
+
+        let end = end.map_or(time, |end| end.min(time));
+        Self((start <= end).then_some((start, Some(end))))
+    }
+
+    /// Restricts to times when `position + velocity * t` lies in `range`.
+    fn within(self, position: i128, velocity: i128, range: &RangeInclusive<i128>) -> Self {
+        if velocity == 0 {
+            return if range.contains(&position) {
+                self
+            } else {
+                Self(None)
+            };
         }
 
-        let slope = self.velocity.y as f64 / self.velocity.x as f64;
-        let intercept = self.position.y as f64 - (slope * self.position.x as f64);
+        let time_at = |value: i128| Rational::new(value - position, velocity).unwrap();
+        let (first, last) = (time_at(*range.start()), time_at(*range.end()));
+        let (first, last) = if velocity > 0 {
+            (first, last)
+        } else {
+            (last, first)
+        };
 
-        Ok(SlopeIntercept { slope, intercept })
+        self.at_least(first).at_most(last)
     }
 
-    fn time_for_x(self, x: f64) -> f64 {
-        (x - self.position.x as f64) / self.velocity.x as f64
+    fn is_some(self) -> bool {
+        self.0.is_some()
     }
 }
 
-#[derive(Debug, Copy, Clone, Eq, PartialEq, Hash)]
-struct Point3D {
-    x: i64,
-    y: i64,
-    z: i64,
-}
-
-impl Display for Point3D {
-    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
-        write!(f, "({}, {}, {})", self.x, self.y, self.z)
+fn part_2(hailstones: &[Hailstone]) -> AdventResult<()> {
+    let throw = find_rock_throw(hailstones)?;
+
+    println!(
+        "Rock thrown from {} at {}",
+        throw.rock.origin, throw.rock.direction
+    );
+    if let (Some(first), Some(last)) = (
+        throw.collision_times.iter().min(),
+        throw.collision_times.iter().max(),
+    ) {
+        println!(
+            "Hits all {} hailstones between t = {first} and t = {last}",
+            throw.collision_times.len()
+        );
     }
+
+    let origin = throw.rock.origin;
+    println!(
+        "Sum of rock initial position components: {}",
+        origin.x + origin.y + origin.z
+    );
+
+    Ok(())
 }
 
-#[derive(Debug, Copy, Clone)]
-struct SlopeIntercept {
-    slope: f64,
-    intercept: f64,
+/// Moves from `origin` with velocity `direction`
+type Hailstone = Ray3<i128>;
+
+#[derive(Debug, Clone)]
+struct RockThrow {
+    rock: Ray3<i128>,
+    /// When the rock hits each hailstone, in the order given
+    collision_times: Vec<i128>,
 }
 
-impl SlopeIntercept {
-    /// Gives (x, y) position of intersection
-    fn intersection(self, other: Self) -> Option<(f64, f64)> {
-        if math::approximately(self.slope, other.slope) {
-            return if math::approximately(self.intercept, other.intercept) {
-                // These are the same line. Any point is valid.
-                Some((0_f64, self.compute_y(0_f64)))
-            } else {
-                // These are parallel lines. No intersection.
-                None
-            };
+/// Finds the one rock throw which hits every hailstone, each at a whole, non-negative time.
+///
+/// A rock at `P` moving with velocity `V` hits hailstone `i` exactly when
+/// `(P - p_i) x (V - v_i) = 0`. The only non-linear term is `P x V`, which is the same for every
+/// hailstone, so subtracting the equations of any two hailstones leaves three linear equations:
+/// `P x (v_j - v_i) + (p_j - p_i) x V = p_j x v_j - p_i x v_i`.
+///
+/// The equations for a pair `(i, j)` are those for `(0, j)` minus those for `(0, i)`,
+/// so pairing every hailstone with the first gives a system with exactly the same solutions
+/// as using every pair, at a fraction of the size.
+fn find_rock_throw(hailstones: &[Hailstone]) -> AdventResult<RockThrow> {
+    let Some((first, others)) = hailstones.split_first() else {
+        return Err(Compute(String::from("No hailstones to throw at")));
+    };
+
+    // Unknowns are P then V, as [Px, Py, Pz, Vx, Vy, Vz].
+    let mut a_values = Grid2D::new(3 * others.len(), 6, 0_i128);
+    let mut b_values = Vec::with_capacity(3 * others.len());
+    for (index, other) in others.iter().enumerate() {
+        let w = other.direction - first.direction;
+        let d = other.origin - first.origin;
+        let c = other.origin.cross(other.direction) - first.origin.cross(first.direction);
+
+        // One row per component of the cross products
+        for (component, (coefficients, value)) in [
+            ([0, w.z, -w.y, 0, -d.z, d.y], c.x),
+            ([-w.z, 0, w.x, d.z, 0, -d.x], c.y),
+            ([w.y, -w.x, 0, -d.y, d.x, 0], c.z),
+        ]
+        .into_iter()
+        .enumerate()
+        {
+            a_values
+                .row_mut_unchecked(3 * index + component)
+                .copy_from_slice(&coefficients);
+            b_values.push(BigRational::from_integer(value.into()));
         }
-
-        let x = (other.intercept - self.intercept) / (self.slope - other.slope);
-        let y = self.compute_y(x);
-        Some((x, y))
     }
 
-    fn compute_y(self, x: f64) -> f64 {
-        self.slope * x + self.intercept
-    }
-}
+    // Intermediate values are well beyond the range of i128 fractions.
+    let solution = match math::solve_exact(&math::exact_grid(&a_values), &b_values)? {
+        LinearSolution::Unique(solution) => solution,
+        LinearSolution::Parametric { .. } => {
+            return Err(Compute(String::from(
+                "Hailstones do not determine a single rock throw",
+            )))
+        }
+        LinearSolution::Inconsistent => {
+            return Err(Compute(String::from(
+                "No single rock throw can hit every hailstone",
+            )))
+        }
+    };
 
-/// Expression of the form a * vx + b * vy + c * x + d * y + g
-/// This is equal to x * vy - y * vx
-/// where our shot is fired from (x, y, z) @ (vx, vy, vz)
-/// (Assume hit at an arbitrary t and manipulate algebra to get here)
-#[derive(Debug, Copy, Clone)]
-struct ExpressionCoefficients {
-    a: i64,
-    b: i64,
-    c: i64,
-    d: i64,
-    g: i64,
+    let components = solution
+        .iter()
+        .map(|value| {
+            value
+                .is_integer()
+                .then(|| value.to_integer().to_i128())
+                .flatten()
+        })
+        .collect::<Option<Vec<_>>>()
+        .ok_or_else(|| {
+            Compute(String::from(
+                "Rock starting position and velocity are not whole numbers within range",
+            ))
+        })?;
+    let rock = Ray3::new(
+        Vector3::new(components[0], components[1], components[2]),
+        Vector3::new(components[3], components[4], components[5]),
+    );
+
+    let collision_times = hailstones
+        .iter()
+        .map(|hailstone| {
+            rock.collision_time(hailstone)
+                .and_then(|time| time.to_integer())
+                .ok_or_else(|| {
+                    Compute(format!(
+                        "Rock {} @ {} does not hit hailstone {} @ {} at a whole, non-negative time",
+                        rock.origin, rock.direction, hailstone.origin, hailstone.direction
+                    ))
+                })
+        })
+        .collect::<AdventResult<_>>()?;
+
+    Ok(RockThrow {
+        rock,
+        collision_times,
+    })
 }
 
 lazy_static! {
@@ -215,24 +294,124 @@ lazy_static! {
         Regex::new(r"(?<x>-?\d+),\s+(?<y>-?\d+),\s+(?<z>-?\d+)").unwrap();
 }
 
-impl FromStr for Point3D {
-    type Err = AdventErr;
+fn parse_hailstone(line: &str) -> AdventResult<Hailstone> {
+    let Some((position, velocity)) = line.split_once('@') else {
+        return Err(InputParse(format!("Failed to split line:\n{line}")));
+    };
 
-    fn from_str(s: &str) -> Result<Self, Self::Err> {
-        let s = s.trim();
-        let Some(caps) = POINT_REGEX.captures(s) else {
-            return Err(InputParse(format!("Failed to parse point: {s}")));
-        };
-        let x = caps["x"]
-            .parse()
-            .map_err(|_| InputParse(format!("Failed to parse x for point: {s}")))?;
-        let y = caps["y"]
-            .parse()
-            .map_err(|_| InputParse(format!("Failed to parse y for point: {s}")))?;
-        let z = caps["z"]
-            .parse()
-            .map_err(|_| InputParse(format!("Failed to parse z for point: {s}")))?;
-
-        Ok(Point3D { x, y, z: z })
+    Ok(Hailstone::new(
+        parse_vector(position)?,
+        parse_vector(velocity)?,
+    ))
+}
+
+fn parse_vector(s: &str) -> AdventResult<Vector3<i128>> {
+    let s = s.trim();
+    let Some(caps) = POINT_REGEX.captures(s) else {
+        return Err(InputParse(format!("Failed to parse point: {s}")));
+    };
+    let x = caps["x"]
+        .parse()
+        .map_err(|_| InputParse(format!("Failed to parse x for point: {s}")))?;
+    let y = caps["y"]
+        .parse()
+        .map_err(|_| InputParse(format!("Failed to parse y for point: {s}")))?;
+    let z = caps["z"]
+        .parse()
+        .map_err(|_| InputParse(format!("Failed to parse z for point: {s}")))?;
+
+    Ok(Vector3::new(x, y, z))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const EXAMPLE: &str = "19, 13, 30 @ -2,  1, -2
+18, 19, 22 @ -1, -1, -2
+20, 25, 34 @ -2, -2, -4
+12, 31, 28 @ -1, -2, -1
+20, 19, 15 @  1, -5, -3";
+
+    fn hailstones(input: &str) -> Vec<Hailstone> {
+        input
+            .lines()
+            .map(|line| parse_hailstone(line).unwrap())
+            .collect()
+    }
+
+    fn crosses(a: &str, b: &str, test_area: RangeInclusive<i128>) -> bool {
+        let (a, b) = (parse_hailstone(a).unwrap(), parse_hailstone(b).unwrap());
+        let forwards = paths_cross_in_area(&a, &b, &test_area).unwrap();
+        assert_eq!(forwards, paths_cross_in_area(&b, &a, &test_area).unwrap());
+        forwards
+    }
+
+    #[test]
+    fn test_path_crossings_example() {
+        assert_eq!(
+            2,
+            count_path_crossings(&hailstones(EXAMPLE), &(7..=27)).unwrap()
+        );
+    }
+
+    #[test]
+    fn test_path_crossings_without_x_motion() {
+        // Meet at (10, 15)
+        let (a, b) = ("10, 0, 0 @ 0, 1, 0", "0, 15, 0 @ 1, 0, 0");
+        assert!(crosses(a, b, 7..=27));
+        assert!(!crosses(a, b, 0..=12));
+
+        // Stays at x = 30 throughout, outside the area
+        assert!(!crosses("30, 0, 0 @ 0, 1, 0", "0, 15, 0 @ 1, 0, 0", 7..=27));
+    }
+
+    #[test]
+    fn test_path_crossings_coincident() {
+        // Same direction, so they share every point from (5, 5) onwards
+        let (a, b) = ("0, 0, 0 @ 1, 1, 0", "5, 5, 0 @ 2, 2, 0");
+        assert!(crosses(a, b, 7..=27));
+        assert!(!crosses(a, b, 0..=4));
+
+        // Towards each other, sharing the points from (0, 0) to (10, 10)
+        let (a, b) = ("0, 0, 0 @ 1, 1, 0", "10, 10, 0 @ -1, -1, 0");
+        assert!(crosses(a, b, 7..=27));
+        assert!(!crosses(a, b, 11..=27));
+
+        // Away from each other, sharing no points
+        assert!(!crosses(
+            "0, 0, 0 @ -1, -1, 0",
+            "10, 10, 0 @ 1, 1, 0",
+            0..=27
+        ));
+    }
+
+    #[test]
+    fn test_rock_throw_example() {
+        let throw = find_rock_throw(&hailstones(EXAMPLE)).unwrap();
+
+        assert_eq!(Vector3::new(24, 13, 10), throw.rock.origin);
+        assert_eq!(Vector3::new(-3, 1, 2), throw.rock.direction);
+        assert_eq!(vec![5, 3, 4, 6, 1], throw.collision_times);
+    }
+
+    #[test]
+    fn test_rock_throw_failures() {
+        // Nudging one hailstone off the rock's path leaves no throw that hits them all.
+        let mut inconsistent = hailstones(EXAMPLE);
+        inconsistent[4] = parse_hailstone("20, 19, 16 @  1, -5, -3").unwrap();
+        assert!(matches!(
+            find_rock_throw(&inconsistent),
+            Err(Compute(message)) if message == "No single rock throw can hit every hailstone"
+        ));
+
+        // Fewer than three hailstones leave the throw underdetermined.
+        for n in [1, 2] {
+            assert!(matches!(
+                find_rock_throw(&hailstones(EXAMPLE)[..n]),
+                Err(Compute(message)) if message == "Hailstones do not determine a single rock throw"
+            ));
+        }
+        assert!(find_rock_throw(&[]).is_err());
     }
 }
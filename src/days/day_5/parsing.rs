@@ -1,4 +1,5 @@
-use crate::days::day_5::types::{Category, CategoryMap, MapRange};
+use crate::data_structures::{LinearSegment, PiecewiseLinearMap};
+use crate::days::day_5::types::{Category, CategoryMap};
 use crate::AdventErr::InputParse;
 use crate::{parser, AdventResult};
 use lazy_static::lazy_static;
@@ -62,17 +63,21 @@ fn block_parser(block: &str) -> AdventResult<CategoryMap> {
         )));
     };
 
-    let mut ranges = parser::as_vec_by_line_from_str(ranges, line_range_parser)?;
-    ranges.sort_unstable_by_key(|x| x.source_start);
+    let segments = parser::as_vec_by_line_from_str(ranges, line_range_parser)?;
+    let Ok(mapping) = PiecewiseLinearMap::new(segments) else {
+        return Err(InputParse(format!(
+            "Overlapping source ranges in block:\n{block}"
+        )));
+    };
 
     Ok(CategoryMap {
         source,
         destination,
-        ranges,
+        mapping,
     })
 }
 
-fn line_range_parser(line: &str) -> AdventResult<MapRange> {
+fn line_range_parser(line: &str) -> AdventResult<LinearSegment<u64>> {
     let mut iter = line.split_whitespace();
 
     let (Some(destination_start), Some(source_start), Some(length)) =
@@ -84,18 +89,50 @@ fn line_range_parser(line: &str) -> AdventResult<MapRange> {
     };
 
     let (Ok(destination_start), Ok(source_start), Ok(length)) = (
-        destination_start.parse(),
-        source_start.parse(),
-        length.parse(),
+        destination_start.parse::<u64>(),
+        source_start.parse::<u64>(),
+        length.parse::<u64>(),
     ) else {
         return Err(InputParse(format!(
             "Failed to parse range values from line:\n{line}"
         )));
     };
 
-    Ok(MapRange {
-        source_start,
+    let Some(source_end) = source_start.checked_add(length) else {
+        return Err(InputParse(format!(
+            "Source range overflows on line:\n{line}"
+        )));
+    };
+
+    if destination_start.checked_add(length).is_none() {
+        return Err(InputParse(format!(
+            "Destination range overflows on line:\n{line}"
+        )));
+    }
+
+    Ok(LinearSegment {
+        source: source_start..source_end,
         destination_start,
-        length,
     })
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_range_overflow() {
+        assert_eq!(
+            LinearSegment {
+                source: 98..100,
+                destination_start: 50,
+            },
+            line_range_parser("50 98 2").unwrap()
+        );
+
+        let max = u64::MAX;
+        assert!(line_range_parser(&format!("0 {max} 1")).is_err());
+        assert!(line_range_parser(&format!("{max} 0 1")).is_err());
+        assert!(line_range_parser(&format!("{} 0 1", max - 1)).is_ok());
+    }
+}
@@ -1,5 +1,4 @@
-use std::cmp::{min, Ordering};
-use std::ops::Range;
+use crate::data_structures::PiecewiseLinearMap;
 
 pub(super) struct SeedData {
     pub seed: u64,
@@ -95,98 +94,15 @@ impl TryFrom<&str> for Category {
     }
 }
 
-#[derive(Debug, Copy, Clone)]
-pub(super) struct MapRange {
-    pub source_start: u64,
-    pub destination_start: u64,
-    pub length: u64,
-}
-
-impl MapRange {
-    /// Exclusive end of source range
-    fn source_end(&self) -> u64 {
-        self.source_start + self.length
-    }
-
-    fn map_value_unchecked(&self, source_value: u64) -> u64 {
-        let offset = source_value - self.source_start;
-        self.destination_start + offset
-    }
-
-    // Returns Equal if the value lies within the range.
-    // Otherwise, returns Less is the range is less than the value,
-    // and Greater if the range is greater than the value
-    fn compare_to_source_value(&self, source_value: u64) -> Ordering {
-        match self.source_start.cmp(&source_value) {
-            Ordering::Greater => Ordering::Greater,
-            Ordering::Equal => Ordering::Equal,
-            Ordering::Less => {
-                if source_value < self.source_end() {
-                    Ordering::Equal
-                } else {
-                    Ordering::Less
-                }
-            }
-        }
-    }
-}
-
 #[derive(Debug)]
 pub(super) struct CategoryMap {
     pub source: Category,
     pub destination: Category,
-    pub ranges: Vec<MapRange>,
+    pub mapping: PiecewiseLinearMap<u64>,
 }
 
 impl CategoryMap {
     fn map_value(&self, source_val: u64) -> u64 {
-        if let Ok(index) = self
-            .ranges
-            .binary_search_by(|test_range| test_range.compare_to_source_value(source_val))
-        {
-            self.ranges[index].map_value_unchecked(source_val)
-        } else {
-            source_val
-        }
-    }
-
-    /// Maps the given source range to one or more destination ranges,
-    /// which are pushed into the provided output vector.
-    pub fn map_range_into(&self, source_range: &Range<u64>, output: &mut Vec<Range<u64>>) {
-        let mut source_start = source_range.start;
-        while source_start < source_range.end {
-            match self
-                .ranges
-                .binary_search_by(|test_range| test_range.compare_to_source_value(source_start))
-            {
-                Ok(index) => {
-                    let mapping_range = &self.ranges[index];
-                    let source_end = min(source_range.end, mapping_range.source_end());
-
-                    let destination_start = mapping_range.map_value_unchecked(source_start);
-                    let destination_end = mapping_range.map_value_unchecked(source_end);
-                    output.push(destination_start..destination_end);
-                    source_start = source_end;
-                }
-
-                Err(index) => match self.ranges.get(index) {
-                    Some(next_mapping_range) => {
-                        let source_end = min(source_range.end, next_mapping_range.source_start);
-
-                        // These values map to themselves.
-                        output.push(source_start..source_end);
-                        source_start = source_end;
-                    }
-
-                    None => {
-                        // All remaining values map to themselves.
-                        let source_end = source_range.end;
-
-                        output.push(source_start..source_end);
-                        break;
-                    }
-                },
-            }
-        }
+        self.mapping.map_value(source_val)
     }
 }
@@ -1,5 +1,6 @@
-use crate::AdventErr::InputParse;
-use crate::{utils, AdventResult};
+use crate::math::{Inequality, IntegerRange};
+use crate::AdventErr::{Compute, InputParse};
+use crate::{math, utils, AdventResult};
 use std::fs::File;
 use std::io::Read;
 
@@ -10,31 +11,33 @@ pub fn run(mut input_file: File) -> AdventResult<()> {
     // Part 1
     let races = parse_multiple_races(&input)?;
     utils::part_header(1);
-    part_1(&races);
+    part_1(&races)?;
 
     // Part 2
     let race = parse_single_race(&input)?;
     utils::part_header(2);
-    part_2(race);
+    part_2(race)?;
 
     Ok(())
 }
 
-fn part_1(races: &[Race]) {
-    let record_beating_product: u64 = races.iter().map(|race| race.winning_options()).product();
+fn part_1(races: &[Race]) -> AdventResult<()> {
+    let record_beating_product: u128 = races
+        .iter()
+        .map(|race| race.winning_options())
+        .product::<AdventResult<_>>()?;
 
     println!("Product of ways to beat records: {record_beating_product}");
+
+    Ok(())
 }
 
-fn part_2(race: Race) {
-    let ways_to_beat_record = race.winning_options();
+fn part_2(race: Race) -> AdventResult<()> {
+    let ways_to_beat_record = race.winning_options()?;
 
     println!("Ways to beat record: {ways_to_beat_record}");
-}
 
-fn compute_distance(hold_time: u64, total_race_time: u64) -> u64 {
-    debug_assert!(hold_time <= total_race_time);
-    hold_time * (total_race_time - hold_time)
+    Ok(())
 }
 
 #[derive(Debug, Copy, Clone)]
@@ -44,34 +47,25 @@ struct Race {
 }
 
 impl Race {
-    fn min_win_hold_time(&self) -> Option<u64> {
-        let time = self.time as f64;
-        let distance = self.record_distance as f64;
-
-        let min_hold_time = ((time - (time * time - 4_f64 * distance).sqrt()) / 2f64).ceil();
-        if min_hold_time.is_nan() {
-            return None;
-        }
-
-        let mut min_hold_time = min_hold_time as u64;
-
-        // In the case where the root is actually an integer, we want to exclude the root itself.
-        // Check just this one case.
-        if compute_distance(min_hold_time, self.time) <= self.record_distance {
-            min_hold_time += 1;
-            if min_hold_time > self.time / 2 {
-                return None;
-            }
-        }
-
-        Some(min_hold_time)
+    /// Holding for `h` travels `h * (time - h)`, so winning needs `-h^2 + time * h - record > 0`.
+    fn winning_hold_times(&self) -> AdventResult<Vec<IntegerRange>> {
+        let (Ok(time), Ok(record_distance)) = (
+            i64::try_from(self.time),
+            i64::try_from(self.record_distance),
+        ) else {
+            return Err(Compute(format!("Race values too large: {self:?}")));
+        };
+
+        math::solve_quadratic_inequality(-1, time, -record_distance, Inequality::Greater)
     }
 
-    fn winning_options(&self) -> u64 {
-        match self.min_win_hold_time() {
-            None => 0,
-            Some(min_time) => self.time - 2 * min_time + 1,
-        }
+    fn winning_options(&self) -> AdventResult<u128> {
+        // The button can only be held for part of the race.
+        Ok(self
+            .winning_hold_times()?
+            .iter()
+            .map(|range| range.count_within(0, self.time as i128))
+            .sum())
     }
 }
 
@@ -48,7 +48,9 @@ fn part_2(instructions: &[Instruction], nodes: &[Node], map_spec: &MapSpec) -> A
     let min_steps = cycles
         .iter()
         .multi_cartesian_product()
-        .filter_map(|cycle_set| find_convergence(&cycle_set))
+        .filter_map(|cycle_set| find_convergence(&cycle_set).transpose())
+        .collect::<AdventResult<Vec<_>>>()?
+        .into_iter()
         .min()
         .ok_or_else(|| Compute(String::from("Unable to find cycle convergence")))?;
 
@@ -129,17 +131,18 @@ fn find_end_cycles(
     cycles
 }
 
-fn find_convergence(cycles: &[&Cycle]) -> Option<u64> {
+/// The first step at which every cycle is at an end node,
+/// or `None` if they never line up.
+fn find_convergence(cycles: &[&Cycle]) -> AdventResult<Option<u128>> {
     let remainders: Vec<_> = cycles.iter().map(|cycle| cycle.offset).collect();
 
     let moduli: Vec<_> = cycles.iter().map(|cycle| cycle.length).collect();
 
-    let base = math::chinese_remainder_theorem(&remainders, &moduli)?;
-    let &min_start = remainders.iter().max().unwrap(); // If empty, would have returned above.
+    let Some(congruence) = math::chinese_remainder_theorem(&remainders, &moduli)? else {
+        return Ok(None);
+    };
 
-    if base >= min_start {
-        Some(base)
-    } else {
-        Some(base + math::lcm(&remainders).unwrap()) // If empty, would have returned above
-    }
+    // Each cycle only starts after its offset.
+    let min_start = remainders.iter().copied().max().unwrap_or(0);
+    congruence.first_at_least(min_start as u128).map(Some)
 }
@@ -0,0 +1,13 @@
+#[allow(unused_imports)]
+pub use distance::{
+    farthest_pair, nearest_pair, sum_pairwise_differences, sum_pairwise_manhattan,
+    sum_pairwise_manhattan_scaling, LinearDistance, PointPair, ScalingPoint,
+};
+#[allow(unused_imports)]
+pub use line::{Line3, Ray3, Scalar, Vector3, XyIntersection};
+#[allow(unused_imports)]
+pub use polygon::{LatticePolygon, Orientation, PointLocation};
+
+mod distance;
+mod line;
+mod polygon;
@@ -0,0 +1,302 @@
+use crate::data_structures::SparsePoint2D;
+use crate::AdventErr::Compute;
+use crate::AdventResult;
+use std::fmt::{Display, Formatter};
+
+/// Reads one coordinate of a point.
+type Axis = fn(SparsePoint2D) -> i64;
+
+const AXES: [Axis; 2] = [|point| point.row, |point| point.col];
+
+/// The sum of `|a - b|` over every pair of values, in `O(n log n)`.
+pub fn sum_pairwise_differences(values: impl IntoIterator<Item = i64>) -> u128 {
+    let mut values: Vec<_> = values.into_iter().collect();
+    values.sort_unstable();
+
+    // Once sorted, each value is at least every value before it,
+    // so it contributes `index * value - (sum of earlier values)`.
+    let mut earlier_sum = 0_i128;
+    let mut total = 0_u128;
+    for (index, &value) in values.iter().enumerate() {
+        total += (index as i128 * value as i128 - earlier_sum) as u128;
+        earlier_sum += value as i128;
+    }
+
+    total
+}
+
+/// The sum of Manhattan distances over every pair of points, in `O(n log n)`.
+/// Each axis contributes independently, so each can be sorted on its own.
+#[allow(dead_code)]
+pub fn sum_pairwise_manhattan(points: &[SparsePoint2D]) -> u128 {
+    AXES.iter()
+        .map(|axis| sum_pairwise_differences(points.iter().map(|&point| axis(point))))
+        .sum()
+}
+
+/// A point at `base + factor * growth`, which spreads out as some factor increases.
+#[derive(Debug, Copy, Clone, Eq, PartialEq, Hash)]
+pub struct ScalingPoint {
+    pub base: SparsePoint2D,
+    pub growth: SparsePoint2D,
+}
+
+#[allow(dead_code)]
+impl ScalingPoint {
+    pub fn new(base: SparsePoint2D, growth: SparsePoint2D) -> Self {
+        Self { base, growth }
+    }
+
+    pub fn at(self, factor: i64) -> SparsePoint2D {
+        SparsePoint2D::new(
+            self.base.row + factor * self.growth.row,
+            self.base.col + factor * self.growth.col,
+        )
+    }
+}
+
+/// A distance of `constant + per_factor * factor`.
+#[derive(Debug, Copy, Clone, Eq, PartialEq, Hash)]
+pub struct LinearDistance {
+    pub constant: u128,
+    pub per_factor: u128,
+}
+
+impl LinearDistance {
+    pub fn evaluate(self, factor: u64) -> u128 {
+        self.constant + self.per_factor * factor as u128
+    }
+}
+
+/// e.g. `82 + 9k`
+impl Display for LinearDistance {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{} + {}k", self.constant, self.per_factor)
+    }
+}
+
+/// The sum of Manhattan distances over every pair of points, for any non-negative factor `k`.
+///
+/// Fails unless the points are in the same order along each axis by base as by growth,
+/// so that no two points pass each other as the factor increases.
+/// Otherwise the distances would only be piecewise linear in the factor.
+pub fn sum_pairwise_manhattan_scaling(points: &[ScalingPoint]) -> AdventResult<LinearDistance> {
+    let mut result = LinearDistance {
+        constant: 0,
+        per_factor: 0,
+    };
+
+    for axis in AXES {
+        let mut coordinates: Vec<_> = points
+            .iter()
+            .map(|point| (axis(point.base), axis(point.growth)))
+            .collect();
+        coordinates.sort_unstable();
+        if coordinates.windows(2).any(|pair| pair[1].1 < pair[0].1) {
+            return Err(Compute(String::from(
+                "Scaling points cross each other as the factor increases",
+            )));
+        }
+
+        // With no crossings, each difference splits into its base and growth parts.
+        result.constant += sum_pairwise_differences(coordinates.iter().map(|&(base, _)| base));
+        result.per_factor +=
+            sum_pairwise_differences(coordinates.iter().map(|&(_, growth)| growth));
+    }
+
+    Ok(result)
+}
+
+/// Two points, by their indices into the slice searched, with `first < second`.
+#[derive(Debug, Copy, Clone, Eq, PartialEq, Hash)]
+pub struct PointPair {
+    pub first: usize,
+    pub second: usize,
+    pub distance: u64,
+}
+
+impl PointPair {
+    fn new(points: &[SparsePoint2D], a: usize, b: usize) -> Self {
+        Self {
+            first: a.min(b),
+            second: a.max(b),
+            distance: points[a].manhattan_distance(points[b]),
+        }
+    }
+}
+
+/// The two points furthest apart by Manhattan distance, in `O(n)`.
+/// Returns `None` if there are fewer than two points.
+#[allow(dead_code)]
+pub fn farthest_pair(points: &[SparsePoint2D]) -> Option<PointPair> {
+    if points.len() < 2 {
+        return None;
+    }
+
+    // The Manhattan distance is the larger of the differences in `row + col` and `row - col`,
+    // so the farthest pair is the pair of extremes of one of those.
+    let diagonals: [Axis; 2] = [|point| point.row + point.col, |point| point.row - point.col];
+    diagonals
+        .into_iter()
+        .filter_map(|diagonal| {
+            let min = (0..points.len()).min_by_key(|&i| diagonal(points[i]))?;
+            let max = (0..points.len()).max_by_key(|&i| diagonal(points[i]))?;
+            Some(PointPair::new(points, min, max))
+        })
+        .max_by_key(|pair| pair.distance)
+}
+
+/// The two points closest together by Manhattan distance, in `O(n log n)`,
+/// by divide and conquer on the rows.
+/// Returns `None` if there are fewer than two points.
+#[allow(dead_code)]
+pub fn nearest_pair(points: &[SparsePoint2D]) -> Option<PointPair> {
+    let mut indices: Vec<_> = (0..points.len()).collect();
+    indices.sort_unstable_by_key(|&i| points[i].row);
+    nearest_pair_sorted(points, &mut indices)
+}
+
+/// `indices` must be sorted by row, and is left sorted by column.
+fn nearest_pair_sorted(points: &[SparsePoint2D], indices: &mut [usize]) -> Option<PointPair> {
+    if indices.len() <= 3 {
+        let mut best: Option<PointPair> = None;
+        for (k, &a) in indices.iter().enumerate() {
+            for &b in &indices[k + 1..] {
+                let candidate = PointPair::new(points, a, b);
+                if best.is_none_or(|best| candidate.distance < best.distance) {
+                    best = Some(candidate);
+                }
+            }
+        }
+        indices.sort_unstable_by_key(|&i| points[i].col);
+        return best;
+    }
+
+    let split = indices.len() / 2;
+    let split_row = points[indices[split]].row;
+    let (above, below) = indices.split_at_mut(split);
+    let mut best = [
+        nearest_pair_sorted(points, above),
+        nearest_pair_sorted(points, below),
+    ]
+    .into_iter()
+    .flatten()
+    .min_by_key(|pair| pair.distance)?;
+
+    // Merge the halves, which are now each sorted by column.
+    let mut merged = Vec::with_capacity(indices.len());
+    let (above, below) = indices.split_at(split);
+    let (mut a, mut b) = (0, 0);
+    while a < above.len() && b < below.len() {
+        if points[above[a]].col <= points[below[b]].col {
+            merged.push(above[a]);
+            a += 1;
+        } else {
+            merged.push(below[b]);
+            b += 1;
+        }
+    }
+    merged.extend_from_slice(&above[a..]);
+    merged.extend_from_slice(&below[b..]);
+    indices.copy_from_slice(&merged);
+
+    // Any closer pair has one point in each half, both closer than `best` to the split row.
+    let strip: Vec<_> = indices
+        .iter()
+        .copied()
+        .filter(|&i| points[i].row.abs_diff(split_row) < best.distance)
+        .collect();
+    for (k, &a) in strip.iter().enumerate() {
+        for &b in &strip[k + 1..] {
+            if points[b].col.abs_diff(points[a].col) >= best.distance {
+                break;
+            }
+
+            let candidate = PointPair::new(points, a, b);
+            if candidate.distance < best.distance {
+                best = candidate;
+            }
+        }
+    }
+
+    Some(best)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Deterministic, scattered points, with some repeats.
+    fn scattered_points(n: usize) -> Vec<SparsePoint2D> {
+        let mut state = 12345_u64;
+        let mut next = || {
+            state = state
+                .wrapping_mul(6364136223846793005)
+                .wrapping_add(1442695040888963407);
+            (state >> 33) as i64 % 200 - 100
+        };
+        (0..n).map(|_| SparsePoint2D::new(next(), next())).collect()
+    }
+
+    fn brute_force_pairs(points: &[SparsePoint2D]) -> Vec<PointPair> {
+        let mut pairs = vec![];
+        for a in 0..points.len() {
+            for b in a + 1..points.len() {
+                pairs.push(PointPair::new(points, a, b));
+            }
+        }
+        pairs
+    }
+
+    #[test]
+    fn test_against_brute_force() {
+        for n in [0, 1, 2, 3, 5, 20, 150] {
+            let points = scattered_points(n);
+            let pairs = brute_force_pairs(&points);
+
+            let total: u128 = pairs.iter().map(|pair| pair.distance as u128).sum();
+            assert_eq!(total, sum_pairwise_manhattan(&points), "n = {n}");
+
+            let nearest = pairs.iter().map(|pair| pair.distance).min();
+            let farthest = pairs.iter().map(|pair| pair.distance).max();
+            assert_eq!(nearest, nearest_pair(&points).map(|pair| pair.distance));
+            assert_eq!(farthest, farthest_pair(&points).map(|pair| pair.distance));
+
+            if let Some(pair) = nearest_pair(&points) {
+                assert!(pair.first < pair.second);
+                assert_eq!(
+                    pair.distance,
+                    points[pair.first].manhattan_distance(points[pair.second])
+                );
+            }
+        }
+    }
+
+    #[test]
+    fn test_scaling() {
+        let p = SparsePoint2D::new;
+        let points = [
+            ScalingPoint::new(p(0, 3), p(0, 2)),
+            ScalingPoint::new(p(1, 7), p(0, 3)),
+            ScalingPoint::new(p(2, 0), p(1, 0)),
+            ScalingPoint::new(p(2, 7), p(1, 3)),
+        ];
+
+        let distance = sum_pairwise_manhattan_scaling(&points).unwrap();
+        for factor in 0..10 {
+            let placed: Vec<_> = points.iter().map(|point| point.at(factor)).collect();
+            assert_eq!(
+                sum_pairwise_manhattan(&placed),
+                distance.evaluate(factor as u64),
+                "factor {factor}: {distance}"
+            );
+        }
+
+        // The second point starts to the right of the first, but grows more slowly.
+        let crossing = [
+            ScalingPoint::new(p(0, 0), p(0, 2)),
+            ScalingPoint::new(p(0, 5), p(0, 1)),
+        ];
+        assert!(sum_pairwise_manhattan_scaling(&crossing).is_err());
+    }
+}
@@ -0,0 +1,381 @@
+use crate::math::Rational;
+use num::Zero;
+use std::fmt::{Display, Formatter};
+use std::ops::{Add, Mul, Neg, Sub};
+
+/// Numbers which vectors can be built from: integers and rationals.
+pub trait Scalar:
+    Copy
+    + Zero
+    + Add<Output = Self>
+    + Sub<Output = Self>
+    + Mul<Output = Self>
+    + Neg<Output = Self>
+    + PartialOrd
+{
+}
+
+impl<T> Scalar for T where
+    T: Copy
+        + Zero
+        + Add<Output = T>
+        + Sub<Output = T>
+        + Mul<Output = T>
+        + Neg<Output = T>
+        + PartialOrd
+{
+}
+
+/// A point or displacement in 3D space.
+#[derive(Debug, Default, Copy, Clone, Eq, PartialEq, Hash)]
+pub struct Vector3<T> {
+    pub x: T,
+    pub y: T,
+    pub z: T,
+}
+
+#[allow(dead_code)]
+impl<T: Scalar> Vector3<T> {
+    pub fn new(x: T, y: T, z: T) -> Self {
+        Self { x, y, z }
+    }
+
+    pub fn scale(self, factor: T) -> Self {
+        Self::new(self.x * factor, self.y * factor, self.z * factor)
+    }
+
+    pub fn dot(self, other: Self) -> T {
+        self.x * other.x + self.y * other.y + self.z * other.z
+    }
+
+    pub fn cross(self, other: Self) -> Self {
+        Self::new(
+            self.y * other.z - self.z * other.y,
+            self.z * other.x - self.x * other.z,
+            self.x * other.y - self.y * other.x,
+        )
+    }
+
+    /// The z component of the cross product of the projections onto the xy plane.
+    /// Positive if `other` is counterclockwise from `self`.
+    pub fn cross_xy(self, other: Self) -> T {
+        self.x * other.y - self.y * other.x
+    }
+
+    /// The dot product of the projections onto the xy plane.
+    pub fn dot_xy(self, other: Self) -> T {
+        self.x * other.x + self.y * other.y
+    }
+
+    pub fn is_zero(self) -> bool {
+        self.x.is_zero() && self.y.is_zero() && self.z.is_zero()
+    }
+
+    /// The projection onto the xy plane is a single point.
+    pub fn is_zero_xy(self) -> bool {
+        self.x.is_zero() && self.y.is_zero()
+    }
+}
+
+#[allow(dead_code)]
+impl Vector3<i128> {
+    pub fn to_rational(self) -> Vector3<Rational> {
+        Vector3::new(
+            Rational::from_integer(self.x),
+            Rational::from_integer(self.y),
+            Rational::from_integer(self.z),
+        )
+    }
+}
+
+impl<T: Scalar> Add for Vector3<T> {
+    type Output = Self;
+
+    fn add(self, rhs: Self) -> Self::Output {
+        Self::new(self.x + rhs.x, self.y + rhs.y, self.z + rhs.z)
+    }
+}
+
+impl<T: Scalar> Sub for Vector3<T> {
+    type Output = Self;
+
+    fn sub(self, rhs: Self) -> Self::Output {
+        Self::new(self.x - rhs.x, self.y - rhs.y, self.z - rhs.z)
+    }
+}
+
+impl<T: Scalar> Neg for Vector3<T> {
+    type Output = Self;
+
+    fn neg(self) -> Self::Output {
+        Self::new(-self.x, -self.y, -self.z)
+    }
+}
+
+impl<T: Display> Display for Vector3<T> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(f, "({}, {}, {})", self.x, self.y, self.z)
+    }
+}
+
+/// How two lines meet once projected onto the xy plane.
+#[derive(Debug, Copy, Clone, Eq, PartialEq)]
+pub enum XyIntersection {
+    /// The projections cross at a single point, reached at `time` along the first line
+    /// and `other_time` along the second.
+    Crossing {
+        time: Rational,
+        other_time: Rational,
+    },
+    /// The projections never meet.
+    Parallel,
+    /// The projections are the same line.
+    Coincident,
+    /// At least one line moves only in z, so its projection is a single point.
+    Degenerate,
+}
+
+/// Every point `point + t * direction`, for any `t`.
+#[derive(Debug, Copy, Clone, Eq, PartialEq, Hash)]
+pub struct Line3<T> {
+    pub point: Vector3<T>,
+    pub direction: Vector3<T>,
+}
+
+#[allow(dead_code)]
+impl<T: Scalar> Line3<T> {
+    pub fn new(point: Vector3<T>, direction: Vector3<T>) -> Self {
+        Self { point, direction }
+    }
+
+    pub fn at(&self, t: T) -> Vector3<T> {
+        self.point + self.direction.scale(t)
+    }
+
+    pub fn is_parallel(&self, other: &Self) -> bool {
+        self.direction.cross(other.direction).is_zero()
+    }
+
+    /// The lines are the same set of points.
+    pub fn is_coincident(&self, other: &Self) -> bool {
+        self.is_parallel(other) && (other.point - self.point).cross(self.direction).is_zero()
+    }
+
+    /// The lines neither meet nor are parallel.
+    pub fn is_skew(&self, other: &Self) -> bool {
+        let normal = self.direction.cross(other.direction);
+        !normal.is_zero() && !(other.point - self.point).dot(normal).is_zero()
+    }
+
+    pub fn intersects(&self, other: &Self) -> bool {
+        if self.is_parallel(other) {
+            self.is_coincident(other)
+        } else {
+            !self.is_skew(other)
+        }
+    }
+}
+
+impl Line3<i128> {
+    /// Where the projections of the lines onto the xy plane meet. The z coordinates are ignored.
+    /// The times are exact, but may overflow if coordinates are far beyond 64 bits.
+    pub fn xy_intersection(&self, other: &Self) -> XyIntersection {
+        if self.direction.is_zero_xy() || other.direction.is_zero_xy() {
+            return XyIntersection::Degenerate;
+        }
+
+        // Solve point + direction * time = other.point + other.direction * other_time.
+        // Crossing both sides with either direction removes one of the unknowns.
+        let offset = other.point - self.point;
+        let denominator = self.direction.cross_xy(other.direction);
+        if denominator == 0 {
+            return if offset.cross_xy(self.direction) == 0 {
+                XyIntersection::Coincident
+            } else {
+                XyIntersection::Parallel
+            };
+        }
+
+        XyIntersection::Crossing {
+            time: Rational::new(offset.cross_xy(other.direction), denominator).unwrap(),
+            other_time: Rational::new(offset.cross_xy(self.direction), denominator).unwrap(),
+        }
+    }
+}
+
+/// Every point `origin + t * direction` with `t >= 0`,
+/// such as the path of something moving from `origin` with velocity `direction`.
+#[derive(Debug, Copy, Clone, Eq, PartialEq, Hash)]
+pub struct Ray3<T> {
+    pub origin: Vector3<T>,
+    pub direction: Vector3<T>,
+}
+
+#[allow(dead_code)]
+impl<T: Scalar> Ray3<T> {
+    pub fn new(origin: Vector3<T>, direction: Vector3<T>) -> Self {
+        Self { origin, direction }
+    }
+
+    /// Returns `None` for negative times, which are not on the ray.
+    pub fn at(&self, t: T) -> Option<Vector3<T>> {
+        (t >= T::zero()).then(|| self.line().at(t))
+    }
+
+    /// The full line the ray lies along.
+    pub fn line(&self) -> Line3<T> {
+        Line3::new(self.origin, self.direction)
+    }
+}
+
+#[allow(dead_code)]
+impl Ray3<i128> {
+    /// The times along each ray at which their xy projections cross,
+    /// or `None` if they do not cross at a single point ahead of both origins.
+    pub fn xy_crossing(&self, other: &Self) -> Option<(Rational, Rational)> {
+        match self.line().xy_intersection(&other.line()) {
+            XyIntersection::Crossing { time, other_time }
+                if time >= Rational::ZERO && other_time >= Rational::ZERO =>
+            {
+                Some((time, other_time))
+            }
+            _ => None,
+        }
+    }
+
+    /// The time at which things leaving both origins together, each moving with its ray's
+    /// direction as velocity, are in the same place. Returns `None` if they never are,
+    /// or only were before they set off.
+    /// Things setting off together from the same place meet immediately.
+    pub fn collision_time(&self, other: &Self) -> Option<Rational> {
+        let offset = other.origin - self.origin;
+        let closing = self.direction - other.direction;
+
+        // offset = closing * time, in every component
+        let mut time = None;
+        for (distance, speed) in [
+            (offset.x, closing.x),
+            (offset.y, closing.y),
+            (offset.z, closing.z),
+        ] {
+            if speed == 0 {
+                if distance != 0 {
+                    return None;
+                }
+                continue;
+            }
+
+            let component_time = Rational::new(distance, speed).unwrap();
+            if time.is_some_and(|time| time != component_time) {
+                return None;
+            }
+            time = Some(component_time);
+        }
+
+        let time = time.unwrap_or(Rational::ZERO);
+        (time >= Rational::ZERO).then_some(time)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn v(x: i128, y: i128, z: i128) -> Vector3<i128> {
+        Vector3::new(x, y, z)
+    }
+
+    fn r(numerator: i128, denominator: i128) -> Rational {
+        Rational::new(numerator, denominator).unwrap()
+    }
+
+    #[test]
+    fn test_vector_products() {
+        let a = v(1, 2, 3);
+        let b = v(4, 5, 6);
+        assert_eq!(32, a.dot(b));
+        assert_eq!(v(-3, 6, -3), a.cross(b));
+        assert_eq!(-3, a.cross_xy(b));
+        assert_eq!(v(5, 7, 9), a + b);
+        assert_eq!(v(3, 3, 3), b - a);
+        assert_eq!(v(2, 4, 6), a.scale(2));
+        assert_eq!("(1, 2, 3)", a.to_string());
+    }
+
+    #[test]
+    fn test_xy_intersection() {
+        // One line is vertical in the xy plane, which a slope-intercept form cannot represent.
+        let vertical = Line3::new(v(3, 0, 0), v(0, 2, 1));
+        let diagonal = Line3::new(v(0, 0, 5), v(1, 1, 0));
+        assert_eq!(
+            XyIntersection::Crossing {
+                time: r(3, 2),
+                other_time: r(3, 1)
+            },
+            vertical.xy_intersection(&diagonal)
+        );
+
+        let shifted = Line3::new(v(0, 1, 0), v(2, 2, 7));
+        assert_eq!(XyIntersection::Parallel, diagonal.xy_intersection(&shifted));
+        let same = Line3::new(v(5, 5, 0), v(-3, -3, 1));
+        assert_eq!(XyIntersection::Coincident, diagonal.xy_intersection(&same));
+        let up = Line3::new(v(5, 5, 0), v(0, 0, 1));
+        assert_eq!(XyIntersection::Degenerate, diagonal.xy_intersection(&up));
+
+        // Crossing behind one of the origins
+        let ray_a = Ray3::new(v(0, 0, 0), v(1, 0, 0));
+        let ray_b = Ray3::new(v(2, 1, 0), v(0, 1, 0));
+        assert_eq!(None, ray_a.xy_crossing(&ray_b));
+        let ray_c = Ray3::new(v(2, -1, 0), v(0, 1, 0));
+        assert_eq!(
+            Some((Rational::from_integer(2), Rational::ONE)),
+            ray_a.xy_crossing(&ray_c)
+        );
+    }
+
+    #[test]
+    fn test_3d_relations() {
+        let x_axis = Line3::new(v(0, 0, 0), v(1, 0, 0));
+        let above_y = Line3::new(v(0, 0, 1), v(0, 1, 0));
+        let through_y = Line3::new(v(5, 0, 0), v(0, 1, 0));
+
+        assert!(x_axis.is_skew(&above_y));
+        assert!(!x_axis.intersects(&above_y));
+        assert!(x_axis.intersects(&through_y));
+        assert!(!x_axis.is_skew(&through_y));
+
+        let parallel = Line3::new(v(0, 1, 0), v(-2, 0, 0));
+        let same = Line3::new(v(7, 0, 0), v(3, 0, 0));
+        assert!(x_axis.is_parallel(&parallel));
+        assert!(!x_axis.is_coincident(&parallel));
+        assert!(!x_axis.is_skew(&parallel));
+        assert!(x_axis.is_coincident(&same));
+        assert!(x_axis.intersects(&same));
+
+        let ray = Ray3::new(v(1, 1, 1), v(1, 2, 3));
+        assert_eq!(Some(v(3, 5, 7)), ray.at(2));
+        assert_eq!(None, ray.at(-1));
+    }
+
+    #[test]
+    fn test_collision_time() {
+        let rock = Ray3::new(v(24, 13, 10), v(-3, 1, 2));
+        let hailstone = Ray3::new(v(19, 13, 30), v(-2, 1, -2));
+        assert_eq!(
+            Some(Rational::from_integer(5)),
+            rock.collision_time(&hailstone)
+        );
+        assert_eq!(
+            Some(Rational::from_integer(5)),
+            hailstone.collision_time(&rock)
+        );
+
+        // Same path, but a step behind
+        let follower = Ray3::new(v(27, 12, 8), v(-3, 1, 2));
+        assert_eq!(None, rock.collision_time(&follower));
+        // Met before setting off
+        let passed = Ray3::new(v(25, 13, 10), v(-2, 1, 2));
+        assert_eq!(None, rock.collision_time(&passed));
+        assert_eq!(Some(Rational::ZERO), rock.collision_time(&rock));
+    }
+}
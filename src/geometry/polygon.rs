@@ -0,0 +1,236 @@
+use crate::data_structures::{Direction, SparsePoint2D};
+use crate::math::gcd;
+use crate::AdventErr::Compute;
+use crate::AdventResult;
+
+/// The direction in which a polygon's vertices run, as drawn with rows increasing downwards.
+#[derive(Debug, Copy, Clone, Eq, PartialEq)]
+pub enum Orientation {
+    Clockwise,
+    Counterclockwise,
+    /// Zero area, so neither
+    Degenerate,
+}
+
+#[derive(Debug, Copy, Clone, Eq, PartialEq)]
+pub enum PointLocation {
+    Inside,
+    Boundary,
+    Outside,
+}
+
+/// A closed polygon whose vertices all lie on integer points.
+/// The last vertex joins back up to the first.
+#[derive(Debug, Clone, Eq, PartialEq)]
+pub struct LatticePolygon {
+    vertices: Vec<SparsePoint2D>,
+}
+
+#[allow(dead_code)]
+impl LatticePolygon {
+    pub fn from_vertices(vertices: Vec<SparsePoint2D>) -> Self {
+        Self { vertices }
+    }
+
+    /// Follows the instructions from the origin, adding a vertex after each one.
+    /// Fails if they do not lead back to the origin.
+    pub fn from_instructions(
+        instructions: impl IntoIterator<Item = (Direction, i64)>,
+    ) -> AdventResult<Self> {
+        let start = SparsePoint2D::new(0, 0);
+        let mut current = start;
+        let mut vertices = vec![];
+        for (direction, length) in instructions {
+            current = current.move_direction(direction, length);
+            vertices.push(current);
+        }
+
+        if current != start {
+            return Err(Compute(format!(
+                "Instructions finish at {current} rather than returning to {start}"
+            )));
+        }
+
+        Ok(Self { vertices })
+    }
+
+    pub fn vertices(&self) -> &[SparsePoint2D] {
+        &self.vertices
+    }
+
+    /// Each edge as `(start, end)`, including the one closing the polygon.
+    fn edges(&self) -> impl Iterator<Item = (SparsePoint2D, SparsePoint2D)> + '_ {
+        self.vertices
+            .iter()
+            .zip(self.vertices.iter().cycle().skip(1))
+            .map(|(&a, &b)| (a, b))
+    }
+
+    /// Twice the area enclosed, by the shoelace formula.
+    /// Doubling keeps the result whole, as lattice polygons may have half-integer areas.
+    /// Positive for counterclockwise polygons and negative for clockwise ones.
+    pub fn signed_double_area(&self) -> i128 {
+        self.edges()
+            .map(|(a, b)| a.row as i128 * b.col as i128 - b.row as i128 * a.col as i128)
+            .sum()
+    }
+
+    /// Twice the area enclosed
+    pub fn double_area(&self) -> u128 {
+        self.signed_double_area().unsigned_abs()
+    }
+
+    pub fn orientation(&self) -> Orientation {
+        match self.signed_double_area().signum() {
+            1 => Orientation::Counterclockwise,
+            -1 => Orientation::Clockwise,
+            _ => Orientation::Degenerate,
+        }
+    }
+
+    /// The number of integer points on the edges.
+    pub fn boundary_points(&self) -> u128 {
+        self.edges()
+            .map(|(a, b)| gcd(a.row.abs_diff(b.row), a.col.abs_diff(b.col)) as u128)
+            .sum()
+    }
+
+    /// The number of integer points strictly inside, by Pick's theorem:
+    /// `area = interior + boundary / 2 - 1`.
+    /// Only meaningful for simple polygons, whose edges do not cross.
+    /// Fails if the boundary is too long for the area, as when edges double back on themselves.
+    pub fn interior_points(&self) -> AdventResult<u128> {
+        (self.double_area() + 2)
+            .checked_sub(self.boundary_points())
+            .map(|double_interior| double_interior / 2)
+            .ok_or_else(|| {
+                Compute(format!(
+                    "Polygon with {} vertices is not simple, so Pick's theorem does not apply",
+                    self.vertices.len()
+                ))
+            })
+    }
+
+    /// The number of integer points inside or on the boundary.
+    pub fn enclosed_points(&self) -> AdventResult<u128> {
+        Ok(self.interior_points()? + self.boundary_points())
+    }
+
+    /// How many times the polygon winds counterclockwise around `point`.
+    /// Points on the boundary give an unspecified result.
+    pub fn winding_number(&self, point: SparsePoint2D) -> i64 {
+        // Work with y increasing upwards, so counterclockwise turns are positive.
+        let to_xy = |p: SparsePoint2D| (p.col as i128, -(p.row as i128));
+        let (px, py) = to_xy(point);
+
+        let mut winding = 0;
+        for (a, b) in self.edges() {
+            let ((ax, ay), (bx, by)) = (to_xy(a), to_xy(b));
+            // Positive if the point is left of the edge a -> b
+            let side = (bx - ax) * (py - ay) - (px - ax) * (by - ay);
+
+            if ay <= py {
+                if by > py && side > 0 {
+                    winding += 1;
+                }
+            } else if by <= py && side < 0 {
+                winding -= 1;
+            }
+        }
+
+        winding
+    }
+
+    pub fn locate(&self, point: SparsePoint2D) -> PointLocation {
+        if self.edges().any(|(a, b)| on_segment(point, a, b)) {
+            PointLocation::Boundary
+        } else if self.winding_number(point) != 0 {
+            PointLocation::Inside
+        } else {
+            PointLocation::Outside
+        }
+    }
+}
+
+fn on_segment(point: SparsePoint2D, a: SparsePoint2D, b: SparsePoint2D) -> bool {
+    let cross = (b.row - a.row) as i128 * (point.col - a.col) as i128
+        - (b.col - a.col) as i128 * (point.row - a.row) as i128;
+
+    cross == 0
+        && a.row.min(b.row) <= point.row
+        && point.row <= a.row.max(b.row)
+        && a.col.min(b.col) <= point.col
+        && point.col <= a.col.max(b.col)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn p(row: i64, col: i64) -> SparsePoint2D {
+        SparsePoint2D::new(row, col)
+    }
+
+    #[test]
+    fn test_square_from_instructions() {
+        // 3x3 square of edges, drawn clockwise: right, down, left, up.
+        let square = LatticePolygon::from_instructions([
+            (Direction::Right, 3),
+            (Direction::Down, 3),
+            (Direction::Left, 3),
+            (Direction::Up, 3),
+        ])
+        .unwrap();
+
+        assert_eq!(-18, square.signed_double_area());
+        assert_eq!(Orientation::Clockwise, square.orientation());
+        assert_eq!(12, square.boundary_points());
+        assert_eq!(4, square.interior_points().unwrap());
+        assert_eq!(16, square.enclosed_points().unwrap());
+
+        let reversed =
+            LatticePolygon::from_vertices(square.vertices().iter().rev().copied().collect());
+        assert_eq!(Orientation::Counterclockwise, reversed.orientation());
+        assert_eq!(1, reversed.winding_number(p(1, 1)));
+        assert_eq!(-1, square.winding_number(p(1, 1)));
+
+        assert!(LatticePolygon::from_instructions([(Direction::Right, 3)]).is_err());
+    }
+
+    #[test]
+    fn test_pick_against_point_location() {
+        // A pentagon with one diagonal edge
+        let polygon =
+            LatticePolygon::from_vertices(vec![p(0, 0), p(0, 2), p(3, 5), p(6, 5), p(6, 0)]);
+
+        let mut interior = 0;
+        let mut boundary = 0;
+        for row in -1..=7 {
+            for col in -1..=6 {
+                match polygon.locate(p(row, col)) {
+                    PointLocation::Inside => interior += 1,
+                    PointLocation::Boundary => boundary += 1,
+                    PointLocation::Outside => {}
+                }
+            }
+        }
+
+        assert_eq!(interior, polygon.interior_points().unwrap());
+        assert_eq!(boundary, polygon.boundary_points());
+    }
+
+    #[test]
+    fn test_degenerate() {
+        let segment = LatticePolygon::from_vertices(vec![p(0, 0), p(2, 2)]);
+        assert_eq!(Orientation::Degenerate, segment.orientation());
+        assert_eq!(4, segment.boundary_points());
+        assert!(segment.interior_points().is_err());
+        assert!(segment.enclosed_points().is_err());
+
+        let there_and_back =
+            LatticePolygon::from_instructions([(Direction::Right, 2), (Direction::Left, 2)])
+                .unwrap();
+        assert_eq!(0, there_and_back.double_area());
+        assert!(there_and_back.enclosed_points().is_err());
+    }
+}
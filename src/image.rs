@@ -0,0 +1,271 @@
+use crate::data_structures::{Grid2D, GridPoint2D};
+use std::fs::{self, File};
+use std::io::{self, BufWriter, Write};
+use std::path::{Path, PathBuf};
+
+pub type Rgb = [u8; 3];
+
+#[allow(dead_code)]
+#[derive(Debug, Copy, Clone, Eq, PartialEq)]
+pub enum ImageFormat {
+    Ppm,
+    Png,
+}
+
+impl ImageFormat {
+    fn extension(self) -> &'static str {
+        match self {
+            ImageFormat::Ppm => "ppm",
+            ImageFormat::Png => "png",
+        }
+    }
+}
+
+/// An RGB image, stored row by row.
+#[derive(Debug, Clone, Eq, PartialEq)]
+pub struct Image {
+    width: usize,
+    height: usize,
+    pixels: Vec<Rgb>,
+}
+
+#[allow(dead_code)]
+impl Image {
+    /// Draws each cell of `grid` as a `scale` x `scale` square, coloured by `color_fn`.
+    pub fn from_grid<T>(grid: &Grid2D<T>, scale: usize, color_fn: impl Fn(&T) -> Rgb) -> Self {
+        let scale = scale.max(1);
+        let width = grid.n_cols() * scale;
+        let height = grid.n_rows() * scale;
+
+        let mut pixels = Vec::with_capacity(width * height);
+        for row in 0..grid.n_rows() {
+            let row_pixels: Vec<Rgb> = grid
+                .row_unchecked(row)
+                .iter()
+                .flat_map(|cell| [color_fn(cell)].repeat(scale))
+                .collect();
+            for _ in 0..scale {
+                pixels.extend_from_slice(&row_pixels);
+            }
+        }
+
+        Self {
+            width,
+            height,
+            pixels,
+        }
+    }
+
+    pub fn width(&self) -> usize {
+        self.width
+    }
+
+    pub fn height(&self) -> usize {
+        self.height
+    }
+
+    /// Returns `None` if the point is outside the image. `row` is the y coordinate.
+    pub fn pixel(&self, point: GridPoint2D) -> Option<Rgb> {
+        if point.row < self.height && point.col < self.width {
+            Some(self.pixels[point.row * self.width + point.col])
+        } else {
+            None
+        }
+    }
+
+    /// Binary (P6) portable pixmap.
+    pub fn write_ppm(&self, out: &mut impl Write) -> io::Result<()> {
+        write!(out, "P6\n{} {}\n255\n", self.width, self.height)?;
+        for pixel in &self.pixels {
+            out.write_all(pixel)?;
+        }
+
+        Ok(())
+    }
+
+    /// 8-bit RGB PNG. The image data is stored without compression,
+    /// which keeps the encoder small at the cost of file size.
+    pub fn write_png(&self, out: &mut impl Write) -> io::Result<()> {
+        out.write_all(&[0x89, b'P', b'N', b'G', b'\r', b'\n', 0x1A, b'\n'])?;
+
+        let mut header = Vec::with_capacity(13);
+        header.extend_from_slice(&(self.width as u32).to_be_bytes());
+        header.extend_from_slice(&(self.height as u32).to_be_bytes());
+        // Bit depth 8, colour type 2 (RGB), default compression, filtering, no interlacing
+        header.extend_from_slice(&[8, 2, 0, 0, 0]);
+        write_png_chunk(out, b"IHDR", &header)?;
+
+        // Every scanline starts with its filter type, which is always 0 (none) here.
+        let mut raw = Vec::with_capacity(self.height * (1 + 3 * self.width));
+        for row in self.pixels.chunks(self.width.max(1)).take(self.height) {
+            raw.push(0);
+            raw.extend(row.iter().flatten());
+        }
+        write_png_chunk(out, b"IDAT", &zlib_stored(&raw))?;
+
+        write_png_chunk(out, b"IEND", &[])
+    }
+
+    pub fn save(&self, path: impl AsRef<Path>, format: ImageFormat) -> io::Result<()> {
+        let mut out = BufWriter::new(File::create(path)?);
+        match format {
+            ImageFormat::Ppm => self.write_ppm(&mut out)?,
+            ImageFormat::Png => self.write_png(&mut out)?,
+        }
+
+        out.flush()
+    }
+}
+
+/// Writes a numbered sequence of images into a directory,
+/// named `<prefix>_00000.<ext>`, `<prefix>_00001.<ext>` and so on,
+/// ready to be stitched into an animation.
+#[derive(Debug)]
+pub struct FrameWriter {
+    directory: PathBuf,
+    prefix: String,
+    format: ImageFormat,
+    next_frame: usize,
+}
+
+#[allow(dead_code)]
+impl FrameWriter {
+    /// Creates the directory if it does not already exist.
+    pub fn new(
+        directory: impl Into<PathBuf>,
+        prefix: &str,
+        format: ImageFormat,
+    ) -> io::Result<Self> {
+        let directory = directory.into();
+        fs::create_dir_all(&directory)?;
+
+        Ok(Self {
+            directory,
+            prefix: prefix.to_string(),
+            format,
+            next_frame: 0,
+        })
+    }
+
+    /// Returns the path written to.
+    pub fn write_frame(&mut self, image: &Image) -> io::Result<PathBuf> {
+        let path = self.directory.join(format!(
+            "{}_{:05}.{}",
+            self.prefix,
+            self.next_frame,
+            self.format.extension()
+        ));
+        image.save(&path, self.format)?;
+        self.next_frame += 1;
+
+        Ok(path)
+    }
+
+    pub fn frames_written(&self) -> usize {
+        self.next_frame
+    }
+}
+
+fn write_png_chunk(out: &mut impl Write, chunk_type: &[u8; 4], data: &[u8]) -> io::Result<()> {
+    out.write_all(&(data.len() as u32).to_be_bytes())?;
+    out.write_all(chunk_type)?;
+    out.write_all(data)?;
+
+    let crc = crc32(chunk_type.iter().chain(data));
+    out.write_all(&crc.to_be_bytes())
+}
+
+/// Wraps `data` in a zlib stream made of uncompressed deflate blocks.
+fn zlib_stored(data: &[u8]) -> Vec<u8> {
+    const MAX_BLOCK: usize = u16::MAX as usize;
+
+    // CMF: deflate with a 32K window. FLG: no dictionary, check bits making CMF.FLG divisible by 31.
+    let mut result = vec![0x78, 0x01];
+
+    let mut blocks = data.chunks(MAX_BLOCK).peekable();
+    if blocks.peek().is_none() {
+        // An empty stream still needs one (final, empty) block.
+        result.extend_from_slice(&[0x01, 0x00, 0x00, 0xFF, 0xFF]);
+    }
+    while let Some(block) = blocks.next() {
+        let is_final = blocks.peek().is_none();
+        let len = block.len() as u16;
+        result.push(u8::from(is_final));
+        result.extend_from_slice(&len.to_le_bytes());
+        result.extend_from_slice(&(!len).to_le_bytes());
+        result.extend_from_slice(block);
+    }
+
+    result.extend_from_slice(&adler32(data).to_be_bytes());
+    result
+}
+
+fn crc32<'a>(bytes: impl IntoIterator<Item = &'a u8>) -> u32 {
+    let mut crc = u32::MAX;
+    for &byte in bytes {
+        crc ^= byte as u32;
+        for _ in 0..8 {
+            let mask = (crc & 1).wrapping_neg();
+            crc = (crc >> 1) ^ (0xEDB8_8320 & mask);
+        }
+    }
+
+    !crc
+}
+
+fn adler32(bytes: &[u8]) -> u32 {
+    const MOD_ADLER: u32 = 65521;
+
+    let (a, b) = bytes.iter().fold((1u32, 0u32), |(a, b), &byte| {
+        let a = (a + byte as u32) % MOD_ADLER;
+        (a, (b + a) % MOD_ADLER)
+    });
+
+    (b << 16) | a
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_checksums() {
+        assert_eq!(0xAE42_6082, crc32(b"IEND"));
+        assert_eq!(0x11E6_0398, adler32(b"Wikipedia"));
+    }
+
+    #[test]
+    fn test_from_grid_scaled() {
+        let grid = Grid2D::from(vec![true, false], 1, 2);
+        let image = Image::from_grid(&grid, 2, |&on| if on { [255, 0, 0] } else { [0, 0, 0] });
+
+        assert_eq!((4, 2), (image.width(), image.height()));
+        assert_eq!(Some([255, 0, 0]), image.pixel(GridPoint2D::new(1, 1)));
+        assert_eq!(Some([0, 0, 0]), image.pixel(GridPoint2D::new(1, 2)));
+        assert_eq!(None, image.pixel(GridPoint2D::new(2, 0)));
+
+        let mut ppm = vec![];
+        image.write_ppm(&mut ppm).unwrap();
+        assert!(ppm.starts_with(b"P6\n4 2\n255\n"));
+        assert_eq!(11 + 4 * 2 * 3, ppm.len());
+    }
+
+    #[test]
+    fn test_png_layout() {
+        let grid = Grid2D::from(vec![[1, 2, 3], [4, 5, 6]], 2, 1);
+        let mut png = vec![];
+        Image::from_grid(&grid, 1, |&rgb| rgb)
+            .write_png(&mut png)
+            .unwrap();
+
+        assert_eq!(b"\x89PNG\r\n\x1a\n", &png[..8]);
+        assert_eq!(b"IHDR", &png[12..16]);
+        assert_eq!(b"IEND\xAE\x42\x60\x82", &png[png.len() - 8..]);
+
+        // IDAT holds a zlib header, one final stored block of both scanlines, and the checksum.
+        let idat_start = 8 + 12 + 13;
+        assert_eq!(b"IDAT", &png[idat_start + 4..idat_start + 8]);
+        let scanlines = [0, 1, 2, 3, 0, 4, 5, 6];
+        assert_eq!(&scanlines, &png[idat_start + 15..idat_start + 23]);
+    }
+}
@@ -3,10 +3,15 @@ use std::fmt::{Debug, Display, Formatter};
 use std::fs::File;
 use std::io::{self, Write};
 
+mod cycle_detection;
 mod data_structures;
 mod days;
+mod geometry;
+mod image;
 mod math;
 mod parser;
+mod render;
+mod search;
 pub mod utils;
 
 type AdventResult<T> = Result<T, AdventErr>;
@@ -1,8 +1,31 @@
-use crate::data_structures::{Grid2D, GridPoint2D};
-use crate::AdventErr::Compute;
-use crate::AdventResult;
-use num::{Integer, Num, Signed, Unsigned};
-use prime_factorization::Factorization;
+use num::{CheckedMul, Integer, Num, Signed, Unsigned};
+
+#[allow(unused_imports)]
+pub use congruence::{chinese_remainder_theorem, Congruence};
+#[allow(unused_imports)]
+pub use gauss_jordan::{determinant, gauss_jordan, inverse, rank, reduced_row_echelon, RowEchelon};
+#[allow(unused_imports)]
+pub use linear_system::{exact_grid, solve_exact, ExactScalar, LinearSolution};
+#[allow(unused_imports)]
+pub use matrix::Matrix;
+#[allow(unused_imports)]
+pub use modular::{addmod, mod_inverse, mulmod, powmod, submod, Mod, ModInt};
+#[allow(unused_imports)]
+pub use polynomial::{sequence_degree, Polynomial};
+#[allow(unused_imports)]
+pub use quadratic::{
+    exact_sqrt, isqrt_u128, isqrt_u64, solve_quadratic_inequality, Inequality, IntegerRange,
+};
+pub use rational::Rational;
+
+mod congruence;
+mod gauss_jordan;
+mod linear_system;
+mod matrix;
+mod modular;
+mod polynomial;
+mod quadratic;
+mod rational;
 
 /// Sign is not guaranteed if one or both input arguments are negative.
 pub fn gcd<T: Num + Copy + Integer>(a: T, b: T) -> T {
@@ -25,15 +48,25 @@ pub fn is_coprime_signed<T: Num + Copy + Signed + Integer>(a: T, b: T) -> bool {
 
 /// Returns `None` if the argument is empty.
 /// Otherwise will return `Some` with the least common multiple of the values.
+#[allow(dead_code)]
 pub fn lcm<T: Num + Copy + Unsigned + Integer>(values: &[T]) -> Option<T> {
     values.iter().copied().reduce(|a, b| (a / gcd(a, b)) * b)
 }
 
+/// The least common multiple of the values, which is 1 if there are none.
+/// Returns `None` if the result overflows.
+#[allow(dead_code)]
+pub fn checked_lcm<T: Num + Copy + Unsigned + Integer + CheckedMul>(values: &[T]) -> Option<T> {
+    values
+        .iter()
+        .try_fold(T::one(), |a, &b| (a / gcd(a, b)).checked_mul(&b))
+}
+
 /// Computes the gcd, as well as bezout coefficients.
 /// returns `(gcd, m, n)` where `am + bn = gcd`.
 ///
 /// Sign of the gcd is not guaranteed if one or both of the arguments is negative.
-fn extended_euclidean<T: Num + Copy + Signed + Integer>(a: T, b: T) -> (T, T, T) {
+pub fn extended_euclidean<T: Num + Copy + Signed + Integer>(a: T, b: T) -> (T, T, T) {
     fn internal<T: Num + Copy + Integer>(r0: T, r1: T, s0: T, s1: T, t0: T, t1: T) -> (T, T, T) {
         if r1.is_zero() {
             (r0, s0, t0)
@@ -48,120 +81,6 @@ fn extended_euclidean<T: Num + Copy + Signed + Integer>(a: T, b: T) -> (T, T, T)
     internal(a, b, T::one(), T::zero(), T::zero(), T::one())
 }
 
-/// Computes a value satisfying the system of congruences provided by the inputs.
-/// The moduli are not required to be pairwise coprime to call this function,
-/// but if they are not, the possibility of no solution exists.
-/// In such a situation, `None` is returned.
-pub fn chinese_remainder_theorem(remainders: &[u64], moduli: &[u64]) -> Option<u64> {
-    if remainders.len() != moduli.len() {
-        return None;
-    }
-
-    let init_modulus = moduli[0];
-    let init_remainder = remainders[0].rem_euclid(init_modulus);
-
-    remainders
-        .iter()
-        .zip(moduli)
-        .skip(1)
-        .try_fold((init_remainder, init_modulus), |(a1, n1), (&a2, &n2)| {
-            // Handle possibility that n1 and n2 may not be coprime.
-            // If they aren't, we need to check that the system is solvable,
-            // and simplify if so.
-            let gcd = gcd(n1, n2);
-            if a1.rem_euclid(gcd) != a2.rem_euclid(gcd) {
-                // There's disagreement on the remainder for the shared gcd. No solution.
-                return None;
-            }
-
-            // Now force n2 to be coprime to n1
-            let n2 = if gcd == 1 {
-                n2
-            } else {
-                let prime_factorization = Factorization::run(gcd);
-                prime_factorization
-                    .factors
-                    .iter()
-                    .fold(n2, |mut current, &factor| {
-                        while current % factor == 0 {
-                            current /= factor;
-                        }
-                        current
-                    })
-            };
-            let new_mod = n1 * n2;
-
-            let (_, m1, m2) = extended_euclidean(n1 as i128, n2 as i128);
-            let solution = (a1 as i128 * m2 * n2 as i128 + a2 as i128 * m1 * n1 as i128)
-                .rem_euclid(new_mod as i128) as u64;
-
-            Some((solution, new_mod))
-        })
-        .map(|(solution, _)| solution)
-}
-
-/// Panics if the length of `b` is different from the number of columns in `a`,
-/// or if `a` is not square
-pub fn gauss_jordan(a: &mut Grid2D<f64>, b: &mut [f64]) -> AdventResult<()> {
-    assert_eq!(b.len(), a.n_cols());
-    assert_eq!(a.n_rows(), a.n_cols());
-
-    // To be used only once the pivot is 1.
-    fn eliminate(pivot_row: usize, target_row: usize, a: &mut Grid2D<f64>, b: &mut [f64]) {
-        use GridPoint2D as P;
-        let factor = -*a.get_unchecked(P::new(target_row, pivot_row));
-
-        for col_num in 0..a.n_cols() {
-            let pivot_row_value = *a.get_unchecked(P::new(pivot_row, col_num));
-            *a.get_mut_unchecked(P::new(target_row, col_num)) += pivot_row_value * factor;
-        }
-
-        *b.get_mut(target_row).unwrap() += b.get(pivot_row).unwrap() * factor;
-    }
-
-    fn pivot_pt(row: usize) -> GridPoint2D {
-        GridPoint2D::new(row, row)
-    }
-
-    for current_row in 0..a.n_rows() {
-        // If current pivot is 0, we need to swap
-        if approximately(*a.get_unchecked(pivot_pt(current_row)), 0_f64) {
-            let mut swap_row = None;
-            for candidate_row in (current_row + 1)..a.n_rows() {
-                if !approximately(*a.get_unchecked(pivot_pt(current_row)), 0_f64) {
-                    swap_row = Some(candidate_row);
-                    break;
-                }
-            }
-
-            let Some(swap_row) = swap_row else {
-                return Err(Compute(String::from(
-                    "Gauss-Jordan failed. Dependent equations",
-                )));
-            };
-
-            a.swap_rows(current_row, swap_row);
-            b.swap(current_row, swap_row);
-        }
-
-        // Divide this row by its pivot so the pivot becomes 1.
-        let pivot = *a.get_unchecked(pivot_pt(current_row));
-        a.map_row_unchecked(current_row, |&value| value / pivot);
-        *b.get_mut(current_row).unwrap() /= pivot;
-
-        // Eliminate
-        for target_row in 0..a.n_rows() {
-            if target_row == current_row {
-                continue;
-            }
-
-            eliminate(current_row, target_row, a, b);
-        }
-    }
-
-    Ok(())
-}
-
 pub const EPSILON: f64 = 1e-6;
 
 pub fn approximately(a: f64, b: f64) -> bool {
@@ -264,21 +183,9 @@ mod tests {
     }
 
     #[test]
-    fn test_crt() {
-        let cases = [
-            (vec![2u64, 3, 2], vec![3u64, 5, 7], 23u64),
-            (vec![1, 1], vec![4, 2], 1),
-            (vec![1, 1], vec![2, 4], 1),
-        ];
-
-        for (remainders, moduli, expected) in cases {
-            assert_eq!(
-                expected,
-                chinese_remainder_theorem(&remainders, &moduli).unwrap(),
-                "CRT: Rem {remainders:?} | Moduli {moduli:?}"
-            );
-        }
-
-        assert_eq!(None, chinese_remainder_theorem(&[2, 3], &[10, 15]));
+    fn test_checked_lcm() {
+        assert_eq!(Some(12u64), checked_lcm(&[4, 6]));
+        assert_eq!(Some(1u64), checked_lcm(&[]));
+        assert_eq!(None, checked_lcm(&[u64::MAX, u64::MAX - 1]));
     }
 }
@@ -0,0 +1,197 @@
+use crate::math::{gcd, ModInt};
+use crate::AdventErr::Compute;
+use crate::{AdventErr, AdventResult};
+use std::fmt::{Display, Formatter};
+
+/// Every integer `x` with `x = residue (mod modulus)`.
+/// The residue is always reduced to `0..modulus`.
+#[derive(Debug, Copy, Clone, Eq, PartialEq, Hash)]
+pub struct Congruence {
+    pub residue: u128,
+    pub modulus: u128,
+}
+
+impl Congruence {
+    /// Every integer, which is the solution to an empty system.
+    pub const ALL: Self = Self {
+        residue: 0,
+        modulus: 1,
+    };
+
+    /// Returns `None` if `modulus` is zero.
+    #[allow(dead_code)]
+    pub fn new(residue: u128, modulus: u128) -> Option<Self> {
+        (modulus > 0).then(|| Self {
+            residue: residue % modulus,
+            modulus,
+        })
+    }
+
+    #[allow(dead_code)]
+    pub fn contains(self, value: u128) -> bool {
+        value % self.modulus == self.residue
+    }
+
+    /// The smallest solution which is at least `lower_bound`.
+    /// Fails if that solution does not fit in a u128.
+    pub fn first_at_least(self, lower_bound: u128) -> AdventResult<u128> {
+        if lower_bound <= self.residue {
+            return Ok(self.residue);
+        }
+
+        let steps = (lower_bound - self.residue).div_ceil(self.modulus);
+        steps
+            .checked_mul(self.modulus)
+            .and_then(|offset| offset.checked_add(self.residue))
+            .ok_or_else(|| {
+                Compute(format!(
+                    "First solution of {self} from {lower_bound} overflows u128"
+                ))
+            })
+    }
+
+    /// The values satisfying both `self` and `x = remainder (mod modulus)`,
+    /// or `None` if there are none.
+    /// Fails if the combined modulus does not fit in a u128.
+    fn combine(self, remainder: u64, modulus: u64) -> AdventResult<Option<Self>> {
+        let (a1, n1) = (self.residue, self.modulus);
+        let (a2, n2) = ((remainder % modulus) as u128, modulus as u128);
+
+        // The moduli need not be coprime, in which case the remainders
+        // must agree on their shared factor for there to be a solution.
+        let gcd = gcd(n1, n2);
+        if a1 % gcd != a2 % gcd {
+            return Ok(None);
+        }
+
+        // x = a1 + n1 k, where (n1 / gcd) k = (a2 - a1) / gcd (mod n2 / gcd).
+        // n2 / gcd fits in a u64, so the arithmetic for k can be done in a ModInt.
+        let reduced_modulus = (n2 / gcd) as u64;
+        let reduce =
+            |value: u128| ModInt::new((value % reduced_modulus as u128) as u64, reduced_modulus);
+        let difference = reduce(a2 / gcd) - reduce(a1 / gcd);
+        let k = difference
+            * reduce(n1 / gcd)
+                .inverse()
+                .expect("n1 / gcd and n2 / gcd are coprime");
+
+        let Some(new_modulus) = n1.checked_mul(reduced_modulus as u128) else {
+            return Err(overflow(n1, n2));
+        };
+
+        // a1 + n1 k < n1 + n1 (n2 / gcd - 1), which is the new modulus.
+        Ok(Some(Self {
+            residue: a1 + n1 * k.value() as u128,
+            modulus: new_modulus,
+        }))
+    }
+}
+
+fn overflow(n1: u128, n2: u128) -> AdventErr {
+    Compute(format!("Combined modulus of {n1} and {n2} overflows u128"))
+}
+
+/// e.g. `2 (mod 7)`
+impl Display for Congruence {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{} (mod {})", self.residue, self.modulus)
+    }
+}
+
+/// Solves the system `x = remainders[i] (mod moduli[i])`.
+/// The moduli are not required to be pairwise coprime,
+/// but if they are not, the possibility of no solution exists.
+/// In such a situation, `Ok(None)` is returned.
+///
+/// An empty system is satisfied by every integer.
+/// Fails if the slices differ in length, if any modulus is zero,
+/// or if the combined modulus overflows.
+pub fn chinese_remainder_theorem(
+    remainders: &[u64],
+    moduli: &[u64],
+) -> AdventResult<Option<Congruence>> {
+    if remainders.len() != moduli.len() {
+        return Err(Compute(format!(
+            "CRT needs one modulus per remainder. Found {} remainders and {} moduli",
+            remainders.len(),
+            moduli.len()
+        )));
+    }
+
+    if moduli.contains(&0) {
+        return Err(Compute(String::from("CRT moduli must be positive")));
+    }
+
+    let mut solution = Congruence::ALL;
+    for (&remainder, &modulus) in remainders.iter().zip(moduli) {
+        match solution.combine(remainder, modulus)? {
+            Some(combined) => solution = combined,
+            None => return Ok(None),
+        }
+    }
+
+    Ok(Some(solution))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_crt() {
+        let cases = [
+            (vec![2u64, 3, 2], vec![3u64, 5, 7], (23, 105)),
+            (vec![1, 1], vec![4, 2], (1, 4)),
+            (vec![1, 1], vec![2, 4], (1, 4)),
+            (vec![7, 2], vec![10, 15], (17, 30)),
+            (vec![], vec![], (0, 1)),
+        ];
+
+        for (remainders, moduli, (residue, modulus)) in cases {
+            assert_eq!(
+                Some(Congruence { residue, modulus }),
+                chinese_remainder_theorem(&remainders, &moduli).unwrap(),
+                "CRT: Rem {remainders:?} | Moduli {moduli:?}"
+            );
+        }
+
+        assert_eq!(None, chinese_remainder_theorem(&[2, 3], &[10, 15]).unwrap());
+        assert!(chinese_remainder_theorem(&[1], &[]).is_err());
+        assert!(chinese_remainder_theorem(&[1], &[0]).is_err());
+    }
+
+    #[test]
+    fn test_crt_wide() {
+        // Three large primes, whose product needs more than 64 bits.
+        let moduli = [4_294_967_291, 4_294_967_279, 4_294_967_231];
+        let solution = chinese_remainder_theorem(&[5, 6, 7], &moduli)
+            .unwrap()
+            .unwrap();
+
+        assert_eq!(
+            moduli.iter().map(|&m| m as u128).product::<u128>(),
+            solution.modulus
+        );
+        for (remainder, modulus) in [5, 6, 7].into_iter().zip(moduli) {
+            assert_eq!(remainder, solution.residue % modulus as u128);
+        }
+
+        let too_many = [u64::MAX, u64::MAX - 1, u64::MAX - 2];
+        assert!(chinese_remainder_theorem(&[0, 0, 0], &too_many).is_err());
+    }
+
+    #[test]
+    fn test_first_at_least() {
+        let congruence = Congruence::new(3, 10).unwrap();
+        assert_eq!(3, congruence.first_at_least(0).unwrap());
+        assert_eq!(3, congruence.first_at_least(3).unwrap());
+        assert_eq!(13, congruence.first_at_least(4).unwrap());
+        assert_eq!(23, congruence.first_at_least(23).unwrap());
+        assert!(congruence.contains(43));
+        assert!(!congruence.contains(44));
+        assert_eq!(None, Congruence::new(1, 0));
+
+        let huge = Congruence::new(0, u128::MAX - 1).unwrap();
+        assert!(huge.first_at_least(u128::MAX).is_err());
+    }
+}
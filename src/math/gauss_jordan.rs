@@ -0,0 +1,303 @@
+use crate::data_structures::{Grid2D, GridPoint2D};
+use crate::math::approximately;
+use crate::AdventErr::Compute;
+use crate::{AdventErr, AdventResult};
+
+/// The reduced row-echelon form of a matrix.
+#[derive(Debug, Clone, PartialEq)]
+pub struct RowEchelon {
+    pub matrix: Grid2D<f64>,
+    /// The column of the leading 1 in each non-zero row, from the top.
+    pub pivot_columns: Vec<usize>,
+}
+
+impl RowEchelon {
+    pub fn rank(&self) -> usize {
+        self.pivot_columns.len()
+    }
+}
+
+/// Reduces `matrix` in place, looking for pivots only in the first `n_pivot_cols` columns.
+/// Row operations still apply to every column, so any further columns act as augmented ones.
+///
+/// Each pivot is the largest magnitude candidate in its column, which keeps rounding errors small.
+/// Values within `EPSILON` of 0 are treated as 0.
+///
+/// Returns the pivot columns and the determinant,
+/// which is only meaningful when reducing a square matrix on all of its columns.
+fn reduce(matrix: &mut Grid2D<f64>, n_pivot_cols: usize) -> (Vec<usize>, f64) {
+    let mut pivot_columns = vec![];
+    let mut determinant = 1_f64;
+
+    for column in 0..n_pivot_cols {
+        let pivot_row = pivot_columns.len();
+        if pivot_row == matrix.n_rows() {
+            break;
+        }
+
+        let candidate = (pivot_row..matrix.n_rows())
+            .map(|row| (row, *matrix.get_unchecked(GridPoint2D::new(row, column))))
+            .max_by(|(_, a), (_, b)| a.abs().total_cmp(&b.abs()))
+            .unwrap();
+        let (candidate_row, pivot) = candidate;
+
+        if approximately(pivot, 0_f64) {
+            // No usable pivot, so this column is free.
+            determinant = 0_f64;
+            continue;
+        }
+
+        if candidate_row != pivot_row {
+            matrix.swap_rows(pivot_row, candidate_row);
+            determinant = -determinant;
+        }
+
+        determinant *= pivot;
+        matrix.map_row_unchecked(pivot_row, |&value| value / pivot);
+
+        let pivot_values = matrix.row_unchecked(pivot_row).to_vec();
+        for target_row in 0..matrix.n_rows() {
+            if target_row == pivot_row {
+                continue;
+            }
+
+            let factor = *matrix.get_unchecked(GridPoint2D::new(target_row, column));
+            if factor == 0_f64 {
+                continue;
+            }
+
+            for (value, pivot_value) in matrix
+                .row_mut_unchecked(target_row)
+                .iter_mut()
+                .zip(&pivot_values)
+            {
+                *value -= pivot_value * factor;
+            }
+        }
+
+        pivot_columns.push(column);
+    }
+
+    (pivot_columns, determinant)
+}
+
+/// Works for matrices of any shape.
+pub fn reduced_row_echelon(matrix: &Grid2D<f64>) -> RowEchelon {
+    let n_cols = matrix.n_cols();
+    let mut matrix = matrix.clone();
+    let (pivot_columns, _) = reduce(&mut matrix, n_cols);
+
+    RowEchelon {
+        matrix,
+        pivot_columns,
+    }
+}
+
+#[allow(dead_code)]
+pub fn rank(matrix: &Grid2D<f64>) -> usize {
+    reduced_row_echelon(matrix).rank()
+}
+
+fn require_square(matrix: &Grid2D<f64>, operation: &str) -> AdventResult<()> {
+    if matrix.is_square() {
+        Ok(())
+    } else {
+        Err(Compute(format!(
+            "Cannot compute {operation} of a {}x{} matrix. It must be square",
+            matrix.n_rows(),
+            matrix.n_cols()
+        )))
+    }
+}
+
+#[allow(dead_code)]
+pub fn determinant(matrix: &Grid2D<f64>) -> AdventResult<f64> {
+    require_square(matrix, "determinant")?;
+
+    let n_cols = matrix.n_cols();
+    let (_, determinant) = reduce(&mut matrix.clone(), n_cols);
+    Ok(determinant)
+}
+
+/// Copies `left` and `right` side by side. Both must have the same number of rows.
+fn augment(left: &Grid2D<f64>, right: &Grid2D<f64>) -> Grid2D<f64> {
+    let n_cols = left.n_cols() + right.n_cols();
+    let mut cells = Vec::with_capacity(left.n_rows() * n_cols);
+    for row in 0..left.n_rows() {
+        cells.extend_from_slice(left.row_unchecked(row));
+        cells.extend_from_slice(right.row_unchecked(row));
+    }
+
+    Grid2D::from(cells, left.n_rows(), n_cols)
+}
+
+fn singular_error(rank: usize, size: usize) -> AdventErr {
+    Compute(format!(
+        "Matrix is singular (rank {rank} of {size}). Dependent equations"
+    ))
+}
+
+#[allow(dead_code)]
+pub fn inverse(matrix: &Grid2D<f64>) -> AdventResult<Grid2D<f64>> {
+    require_square(matrix, "inverse")?;
+
+    let size = matrix.n_rows();
+    let mut identity = Grid2D::new(size, size, 0_f64);
+    for i in 0..size {
+        *identity.get_mut_unchecked(GridPoint2D::new(i, i)) = 1_f64;
+    }
+
+    let mut augmented = augment(matrix, &identity);
+    let (pivot_columns, _) = reduce(&mut augmented, size);
+    if pivot_columns.len() < size {
+        return Err(singular_error(pivot_columns.len(), size));
+    }
+
+    let cells = (0..size)
+        .flat_map(|row| augmented.row_unchecked(row)[size..].to_vec())
+        .collect();
+    Ok(Grid2D::from(cells, size, size))
+}
+
+/// Solves `a x = b`, leaving the identity in `a` and the solution in `b`.
+///
+/// Fails if `a` is not square, if `b` does not have one value per row,
+/// or if the equations are not independent.
+#[allow(dead_code)]
+pub fn gauss_jordan(a: &mut Grid2D<f64>, b: &mut [f64]) -> AdventResult<()> {
+    require_square(a, "Gauss-Jordan elimination")?;
+    if b.len() != a.n_rows() {
+        return Err(Compute(format!(
+            "Gauss-Jordan failed. {} equations but {} right-hand values",
+            a.n_rows(),
+            b.len()
+        )));
+    }
+
+    let size = a.n_rows();
+    let mut augmented = augment(a, &Grid2D::from(b.to_vec(), size, 1));
+    let (pivot_columns, _) = reduce(&mut augmented, size);
+    if pivot_columns.len() < size {
+        return Err(singular_error(pivot_columns.len(), size));
+    }
+
+    for (row, solution) in b.iter_mut().enumerate() {
+        let values = augmented.row_unchecked(row);
+        a.row_mut_unchecked(row).copy_from_slice(&values[..size]);
+        *solution = values[size];
+    }
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn matrix(rows: &[&[f64]]) -> Grid2D<f64> {
+        let cells = rows.iter().flat_map(|row| row.iter().copied()).collect();
+        Grid2D::from(cells, rows.len(), rows[0].len())
+    }
+
+    fn assert_close(expected: &Grid2D<f64>, actual: &Grid2D<f64>) {
+        assert_eq!(
+            (expected.n_rows(), expected.n_cols()),
+            (actual.n_rows(), actual.n_cols())
+        );
+        for (e, a) in expected.cells().zip(actual.cells()) {
+            assert!(
+                approximately(*e, *a),
+                "expected\n{expected:?}\nactual\n{actual:?}"
+            );
+        }
+    }
+
+    #[test]
+    fn test_gauss_jordan_needs_swap() {
+        // The first pivot is zero, so a lower row has to be swapped in.
+        let mut a = matrix(&[&[0.0, 2.0, 1.0], &[1.0, 1.0, 0.0], &[2.0, 0.0, 3.0]]);
+        let mut b = [7.0, 3.0, 4.0];
+        gauss_jordan(&mut a, &mut b).unwrap();
+
+        assert_close(
+            &matrix(&[&[1.0, 0.0, 0.0], &[0.0, 1.0, 0.0], &[0.0, 0.0, 1.0]]),
+            &a,
+        );
+        let expected = [1.0 / 8.0, 23.0 / 8.0, 5.0 / 4.0];
+        for (e, x) in expected.iter().zip(b) {
+            assert!(approximately(*e, x), "{expected:?} vs {b:?}");
+        }
+    }
+
+    #[test]
+    fn test_gauss_jordan_errors() {
+        let mut singular = matrix(&[&[1.0, 2.0], &[2.0, 4.0]]);
+        assert!(gauss_jordan(&mut singular, &mut [1.0, 2.0]).is_err());
+
+        let mut a = matrix(&[&[1.0, 0.0], &[0.0, 1.0]]);
+        assert!(gauss_jordan(&mut a, &mut [1.0]).is_err());
+
+        let mut non_square = matrix(&[&[1.0, 0.0, 1.0], &[0.0, 1.0, 1.0]]);
+        assert!(gauss_jordan(&mut non_square, &mut [1.0, 1.0]).is_err());
+    }
+
+    #[test]
+    fn test_reduced_row_echelon_non_square() {
+        let echelon = reduced_row_echelon(&matrix(&[
+            &[1.0, 2.0, 1.0, 4.0],
+            &[2.0, 4.0, 0.0, 6.0],
+            &[3.0, 6.0, 1.0, 10.0],
+        ]));
+
+        assert_eq!(vec![0, 2], echelon.pivot_columns);
+        assert_eq!(2, echelon.rank());
+        assert_close(
+            &matrix(&[
+                &[1.0, 2.0, 0.0, 3.0],
+                &[0.0, 0.0, 1.0, 1.0],
+                &[0.0, 0.0, 0.0, 0.0],
+            ]),
+            &echelon.matrix,
+        );
+    }
+
+    #[test]
+    fn test_rank() {
+        assert_eq!(2, rank(&matrix(&[&[1.0, 2.0], &[3.0, 4.0], &[5.0, 6.0]])));
+        assert_eq!(1, rank(&matrix(&[&[1.0, 2.0, 3.0], &[-2.0, -4.0, -6.0]])));
+        assert_eq!(0, rank(&matrix(&[&[0.0, 0.0]])));
+    }
+
+    #[test]
+    fn test_determinant() {
+        let cases = [
+            (matrix(&[&[2.0]]), 2.0),
+            (matrix(&[&[1.0, 2.0], &[3.0, 4.0]]), -2.0),
+            (matrix(&[&[0.0, 1.0], &[1.0, 0.0]]), -1.0),
+            (
+                matrix(&[&[2.0, 0.0, 1.0], &[1.0, 3.0, 2.0], &[1.0, 1.0, 2.0]]),
+                6.0,
+            ),
+            (matrix(&[&[1.0, 2.0], &[2.0, 4.0]]), 0.0),
+        ];
+
+        for (matrix, expected) in cases {
+            let actual = determinant(&matrix).unwrap();
+            assert!(approximately(expected, actual), "det {matrix:?} = {actual}");
+        }
+
+        assert!(determinant(&matrix(&[&[1.0, 2.0]])).is_err());
+    }
+
+    #[test]
+    fn test_inverse() {
+        let a = matrix(&[&[4.0, 7.0], &[2.0, 6.0]]);
+        assert_close(
+            &matrix(&[&[0.6, -0.7], &[-0.2, 0.4]]),
+            &inverse(&a).unwrap(),
+        );
+
+        assert!(inverse(&matrix(&[&[1.0, 2.0], &[2.0, 4.0]])).is_err());
+        assert!(inverse(&matrix(&[&[1.0, 2.0]])).is_err());
+    }
+}
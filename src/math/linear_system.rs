@@ -0,0 +1,325 @@
+use crate::data_structures::Grid2D;
+use crate::math::Rational;
+use crate::AdventErr::Compute;
+use crate::{AdventErr, AdventResult};
+use num::{BigInt, BigRational, One, Signed, Zero};
+
+/// Numbers which `solve_exact` can eliminate over without rounding.
+/// Arithmetic reports overflow as `None`; types which cannot overflow always return `Some`.
+pub trait ExactScalar: Clone + Eq {
+    fn zero() -> Self;
+    fn one() -> Self;
+    fn from_integer(value: i128) -> Self;
+    fn is_zero(&self) -> bool;
+    fn checked_neg(&self) -> Option<Self>;
+    fn checked_sub(&self, rhs: &Self) -> Option<Self>;
+    fn checked_mul(&self, rhs: &Self) -> Option<Self>;
+    /// `None` on division by zero, as well as overflow.
+    fn checked_div(&self, rhs: &Self) -> Option<Self>;
+    /// Roughly how many bits are needed to store the value. Used to pick small pivots.
+    fn size(&self) -> u64;
+}
+
+impl ExactScalar for Rational {
+    fn zero() -> Self {
+        Rational::ZERO
+    }
+
+    fn one() -> Self {
+        Rational::ONE
+    }
+
+    fn from_integer(value: i128) -> Self {
+        Rational::from_integer(value)
+    }
+
+    fn is_zero(&self) -> bool {
+        Rational::is_zero(*self)
+    }
+
+    fn checked_neg(&self) -> Option<Self> {
+        Rational::checked_neg(*self)
+    }
+
+    fn checked_sub(&self, rhs: &Self) -> Option<Self> {
+        Rational::checked_sub(*self, *rhs)
+    }
+
+    fn checked_mul(&self, rhs: &Self) -> Option<Self> {
+        Rational::checked_mul(*self, *rhs)
+    }
+
+    fn checked_div(&self, rhs: &Self) -> Option<Self> {
+        Rational::checked_div(*self, *rhs)
+    }
+
+    fn size(&self) -> u64 {
+        let bits = |value: u128| (u128::BITS - value.leading_zeros()) as u64;
+        bits(self.numerator().unsigned_abs()) + bits(self.denominator() as u128)
+    }
+}
+
+/// Never overflows, at the cost of allocating for every value.
+impl ExactScalar for BigRational {
+    fn zero() -> Self {
+        Zero::zero()
+    }
+
+    fn one() -> Self {
+        One::one()
+    }
+
+    fn from_integer(value: i128) -> Self {
+        BigRational::from_integer(BigInt::from(value))
+    }
+
+    fn is_zero(&self) -> bool {
+        Zero::is_zero(self)
+    }
+
+    fn checked_neg(&self) -> Option<Self> {
+        Some(-self)
+    }
+
+    fn checked_sub(&self, rhs: &Self) -> Option<Self> {
+        Some(self - rhs)
+    }
+
+    fn checked_mul(&self, rhs: &Self) -> Option<Self> {
+        Some(self * rhs)
+    }
+
+    fn checked_div(&self, rhs: &Self) -> Option<Self> {
+        (!Zero::is_zero(rhs)).then(|| self / rhs)
+    }
+
+    fn size(&self) -> u64 {
+        self.numer().abs().bits() + self.denom().bits()
+    }
+}
+
+/// The outcome of solving `a x = b` exactly.
+#[derive(Debug, Clone, Eq, PartialEq)]
+pub enum LinearSolution<T> {
+    Unique(Vec<T>),
+    /// Every solution is `particular` plus any combination of the `basis` vectors.
+    /// Each basis vector corresponds to one free variable.
+    Parametric {
+        particular: Vec<T>,
+        basis: Vec<Vec<T>>,
+    },
+    Inconsistent,
+}
+
+impl<T> LinearSolution<T> {
+    /// The solution, or a `Compute` error describing why there isn't exactly one.
+    #[allow(dead_code)]
+    pub fn unique(self) -> AdventResult<Vec<T>> {
+        match self {
+            LinearSolution::Unique(solution) => Ok(solution),
+            LinearSolution::Parametric { basis, .. } => Err(Compute(format!(
+                "Linear system is underdetermined, with {} free variable(s)",
+                basis.len()
+            ))),
+            LinearSolution::Inconsistent => {
+                Err(Compute(String::from("Linear system is inconsistent")))
+            }
+        }
+    }
+}
+
+fn overflow() -> AdventErr {
+    Compute(String::from(
+        "Overflow in exact linear solve. Try a wider number type",
+    ))
+}
+
+/// Solves `a x = b` using Gauss-Jordan elimination over exact fractions.
+/// `a` may have any shape: one row per equation, one column per unknown.
+///
+/// Fails if `b` does not have one value per row of `a`,
+/// or if an intermediate value overflows.
+pub fn solve_exact<T: ExactScalar>(a: &Grid2D<T>, b: &[T]) -> AdventResult<LinearSolution<T>> {
+    if b.len() != a.n_rows() {
+        return Err(Compute(format!(
+            "Linear system has {} equations but {} right-hand values",
+            a.n_rows(),
+            b.len()
+        )));
+    }
+
+    let n_unknowns = a.n_cols();
+
+    // Augmented matrix [a | b]
+    let mut rows: Vec<Vec<T>> = (0..a.n_rows())
+        .map(|row_num| {
+            let mut row = a.row_unchecked(row_num).to_vec();
+            row.push(b[row_num].clone());
+            row
+        })
+        .collect();
+
+    let mut pivot_columns = vec![];
+    for column in 0..n_unknowns {
+        let pivot_row = pivot_columns.len();
+
+        // Any non-zero pivot is exact. Prefer the smallest, to keep the fractions small.
+        let Some(candidate) = (pivot_row..rows.len())
+            .filter(|&row_num| !rows[row_num][column].is_zero())
+            .min_by_key(|&row_num| rows[row_num][column].size())
+        else {
+            continue;
+        };
+        rows.swap(pivot_row, candidate);
+
+        let pivot = rows[pivot_row][column].clone();
+        for value in rows[pivot_row].iter_mut() {
+            *value = value.checked_div(&pivot).ok_or_else(overflow)?;
+        }
+
+        let pivot_values = rows[pivot_row].clone();
+        for (row_num, row) in rows.iter_mut().enumerate() {
+            let factor = row[column].clone();
+            if row_num == pivot_row || factor.is_zero() {
+                continue;
+            }
+
+            for (value, pivot_value) in row.iter_mut().zip(&pivot_values).skip(column) {
+                let scaled = pivot_value.checked_mul(&factor).ok_or_else(overflow)?;
+                *value = value.checked_sub(&scaled).ok_or_else(overflow)?;
+            }
+        }
+
+        pivot_columns.push(column);
+    }
+
+    // Any remaining row reads 0 = b, which only holds if b is also 0.
+    if rows[pivot_columns.len()..]
+        .iter()
+        .any(|row| !row[n_unknowns].is_zero())
+    {
+        return Ok(LinearSolution::Inconsistent);
+    }
+
+    let mut particular = vec![T::zero(); n_unknowns];
+    for (row_num, &column) in pivot_columns.iter().enumerate() {
+        particular[column] = rows[row_num][n_unknowns].clone();
+    }
+
+    if pivot_columns.len() == n_unknowns {
+        return Ok(LinearSolution::Unique(particular));
+    }
+
+    let basis = (0..n_unknowns)
+        .filter(|column| !pivot_columns.contains(column))
+        .map(|free_column| {
+            let mut vector = vec![T::zero(); n_unknowns];
+            vector[free_column] = T::one();
+            for (row_num, &column) in pivot_columns.iter().enumerate() {
+                vector[column] = rows[row_num][free_column]
+                    .checked_neg()
+                    .ok_or_else(overflow)?;
+            }
+            Ok(vector)
+        })
+        .collect::<AdventResult<_>>()?;
+
+    Ok(LinearSolution::Parametric { particular, basis })
+}
+
+/// Converts an integer matrix for use with `solve_exact`.
+pub fn exact_grid<T: ExactScalar>(values: &Grid2D<i128>) -> Grid2D<T> {
+    let cells = values
+        .cells()
+        .map(|&value| T::from_integer(value))
+        .collect();
+    Grid2D::from(cells, values.n_rows(), values.n_cols())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::data_structures::GridPoint2D;
+
+    fn residual(a: &Grid2D<Rational>, x: &[Rational], row_num: usize) -> Rational {
+        (0..a.n_cols())
+            .map(|col| *a.get_unchecked(GridPoint2D::new(row_num, col)) * x[col])
+            .fold(Rational::ZERO, |acc, value| acc + value)
+    }
+
+    fn system(rows: &[&[i128]]) -> (Grid2D<Rational>, Vec<Rational>) {
+        let n_cols = rows[0].len() - 1;
+        let cells = rows
+            .iter()
+            .flat_map(|row| row[..n_cols].iter().copied())
+            .collect();
+        let a = exact_grid(&Grid2D::from(cells, rows.len(), n_cols));
+        let b = rows
+            .iter()
+            .map(|row| Rational::from_integer(row[n_cols]))
+            .collect();
+        (a, b)
+    }
+
+    #[test]
+    fn test_unique() {
+        // Needs a row swap, as the first pivot is zero.
+        let (a, b) = system(&[&[0, 2, 1, 7], &[1, 1, 0, 3], &[2, 0, 3, 4]]);
+        let solution = solve_exact(&a, &b).unwrap().unique().unwrap();
+
+        for (row_num, expected) in b.iter().enumerate() {
+            assert_eq!(*expected, residual(&a, &solution, row_num));
+        }
+        assert_eq!(Rational::new(23, 8).unwrap(), solution[1]);
+    }
+
+    #[test]
+    fn test_overdetermined_consistent() {
+        let (a, b) = system(&[&[1, 1, 3], &[1, -1, 1], &[2, 3, 7]]);
+        let solution = solve_exact(&a, &b).unwrap();
+        assert_eq!(
+            LinearSolution::Unique(vec![Rational::from_integer(2), Rational::ONE]),
+            solution
+        );
+    }
+
+    #[test]
+    fn test_parametric() {
+        let (a, b) = system(&[&[1, 2, 3, 6], &[2, 4, 6, 12]]);
+        let LinearSolution::Parametric { particular, basis } = solve_exact(&a, &b).unwrap() else {
+            panic!("Expected a parametric solution");
+        };
+
+        assert_eq!(2, basis.len());
+        assert_eq!(Rational::from_integer(6), residual(&a, &particular, 0));
+        for vector in basis {
+            assert_eq!(Rational::ZERO, residual(&a, &vector, 0));
+            assert_eq!(Rational::ZERO, residual(&a, &vector, 1));
+        }
+    }
+
+    #[test]
+    fn test_inconsistent_and_errors() {
+        let (a, b) = system(&[&[1, 1, 1], &[1, 1, 2]]);
+        assert_eq!(LinearSolution::Inconsistent, solve_exact(&a, &b).unwrap());
+        assert!(solve_exact(&a, &b[..1]).is_err());
+    }
+
+    #[test]
+    fn test_overflow_and_big_rational() {
+        // Eliminating the first column needs values far beyond i128.
+        let big = 10_i128.pow(30);
+        let a = Grid2D::from(vec![big + 1, big + 3, big + 7, big + 13], 2, 2);
+        let b = [1, 2];
+
+        let small = solve_exact(&exact_grid::<Rational>(&a), &b.map(Rational::from_integer));
+        let big = solve_exact(
+            &exact_grid::<BigRational>(&a),
+            &b.map(|x| BigRational::from_integer(x.into())),
+        )
+        .unwrap();
+
+        assert!(small.is_err());
+        assert!(matches!(big, LinearSolution::Unique(_)));
+    }
+}
@@ -0,0 +1,362 @@
+use crate::data_structures::Grid2D;
+use crate::AdventErr::Compute;
+use crate::AdventResult;
+use num::{CheckedAdd, CheckedMul, One, Zero};
+use std::fmt::{Display, Formatter};
+use std::ops::{Add, Index, IndexMut, Mul};
+
+/// A matrix for arithmetic, stored in row-major order.
+/// Works over any number type with addition and multiplication,
+/// such as the integer types, `Rational` and `f64`.
+///
+/// Arithmetic on matrices of mismatched shapes panics, as with slice indexing.
+#[derive(Debug, Clone, Eq, PartialEq)]
+pub struct Matrix<T> {
+    n_rows: usize,
+    n_cols: usize,
+    values: Vec<T>,
+}
+
+#[allow(dead_code)]
+impl<T> Matrix<T>
+where
+    T: Clone + Zero + One,
+{
+    pub fn zeros(n_rows: usize, n_cols: usize) -> Self {
+        Self {
+            n_rows,
+            n_cols,
+            values: vec![T::zero(); n_rows * n_cols],
+        }
+    }
+
+    pub fn identity(size: usize) -> Self {
+        let mut result = Self::zeros(size, size);
+        for i in 0..size {
+            result[(i, i)] = T::one();
+        }
+
+        result
+    }
+
+    /// Fails if the rows are not all the same length.
+    pub fn from_rows(rows: Vec<Vec<T>>) -> AdventResult<Self> {
+        let n_rows = rows.len();
+        let n_cols = rows.first().map_or(0, Vec::len);
+        if rows.iter().any(|row| row.len() != n_cols) {
+            return Err(Compute(String::from(
+                "Matrix rows must all be the same length",
+            )));
+        }
+
+        Ok(Self {
+            n_rows,
+            n_cols,
+            values: rows.into_iter().flatten().collect(),
+        })
+    }
+
+    pub fn from_grid(grid: &Grid2D<T>) -> Self {
+        Self {
+            n_rows: grid.n_rows(),
+            n_cols: grid.n_cols(),
+            values: grid.cells().cloned().collect(),
+        }
+    }
+
+    pub fn to_grid(&self) -> Grid2D<T> {
+        Grid2D::from(self.values.clone(), self.n_rows, self.n_cols)
+    }
+
+    pub fn n_rows(&self) -> usize {
+        self.n_rows
+    }
+
+    pub fn n_cols(&self) -> usize {
+        self.n_cols
+    }
+
+    pub fn is_square(&self) -> bool {
+        self.n_rows == self.n_cols
+    }
+
+    pub fn row(&self, row_num: usize) -> &[T] {
+        &self.values[row_num * self.n_cols..(row_num + 1) * self.n_cols]
+    }
+
+    pub fn transpose(&self) -> Self {
+        let mut result = Self::zeros(self.n_cols, self.n_rows);
+        for row in 0..self.n_rows {
+            for col in 0..self.n_cols {
+                result[(col, row)] = self[(row, col)].clone();
+            }
+        }
+
+        result
+    }
+
+    /// The product `self * vector`, treating `vector` as a column.
+    /// Panics if `vector` does not have one value per column.
+    pub fn mul_vector(&self, vector: &[T]) -> Vec<T> {
+        assert_eq!(
+            self.n_cols,
+            vector.len(),
+            "Vector length must match matrix columns"
+        );
+
+        (0..self.n_rows)
+            .map(|row_num| dot(self.row(row_num), vector.iter()))
+            .collect()
+    }
+
+    /// `self` multiplied by itself `exp` times, by repeated squaring,
+    /// so only `O(log exp)` matrix products are needed.
+    /// Panics if the matrix is not square.
+    pub fn pow(&self, mut exp: u64) -> Self {
+        assert!(self.is_square(), "Only square matrices have powers");
+
+        let mut result = Self::identity(self.n_rows);
+        let mut base = self.clone();
+        while exp > 0 {
+            if exp & 1 == 1 {
+                result = &result * &base;
+            }
+            exp >>= 1;
+            if exp > 0 {
+                base = &base * &base;
+            }
+        }
+
+        result
+    }
+}
+
+/// Checked arithmetic, for integer matrices whose products may overflow.
+#[allow(dead_code)]
+impl<T> Matrix<T>
+where
+    T: Clone + Zero + One + CheckedAdd + CheckedMul,
+{
+    /// As `self * rhs`, but `None` if any value overflows.
+    /// Panics if `self` does not have one column per row of `rhs`.
+    pub fn checked_mul(&self, rhs: &Self) -> Option<Self> {
+        assert_eq!(
+            self.n_cols, rhs.n_rows,
+            "Cannot multiply {}x{} matrix by {}x{} matrix",
+            self.n_rows, self.n_cols, rhs.n_rows, rhs.n_cols
+        );
+
+        let mut result = Matrix::zeros(self.n_rows, rhs.n_cols);
+        for row in 0..self.n_rows {
+            for col in 0..rhs.n_cols {
+                let column = (0..rhs.n_rows).map(|k| &rhs[(k, col)]);
+                result[(row, col)] = checked_dot(self.row(row), column)?;
+            }
+        }
+
+        Some(result)
+    }
+
+    /// As `mul_vector`, but `None` if any value overflows.
+    pub fn checked_mul_vector(&self, vector: &[T]) -> Option<Vec<T>> {
+        assert_eq!(
+            self.n_cols,
+            vector.len(),
+            "Vector length must match matrix columns"
+        );
+
+        (0..self.n_rows)
+            .map(|row_num| checked_dot(self.row(row_num), vector.iter()))
+            .collect()
+    }
+
+    /// As `pow`, but `None` if any value overflows.
+    pub fn checked_pow(&self, mut exp: u64) -> Option<Self> {
+        assert!(self.is_square(), "Only square matrices have powers");
+
+        let mut result = Self::identity(self.n_rows);
+        let mut base = self.clone();
+        while exp > 0 {
+            if exp & 1 == 1 {
+                result = result.checked_mul(&base)?;
+            }
+            exp >>= 1;
+            if exp > 0 {
+                base = base.checked_mul(&base)?;
+            }
+        }
+
+        Some(result)
+    }
+}
+
+fn checked_dot<'a, T>(row: &'a [T], column: impl Iterator<Item = &'a T>) -> Option<T>
+where
+    T: Clone + Zero + CheckedAdd + CheckedMul + 'a,
+{
+    row.iter()
+        .zip(column)
+        .try_fold(T::zero(), |acc, (a, b)| acc.checked_add(&a.checked_mul(b)?))
+}
+
+fn dot<'a, T>(row: &'a [T], column: impl Iterator<Item = &'a T>) -> T
+where
+    T: Clone + Zero + One + 'a,
+{
+    row.iter()
+        .zip(column)
+        .fold(T::zero(), |acc, (a, b)| acc + a.clone() * b.clone())
+}
+
+impl<T> Index<(usize, usize)> for Matrix<T> {
+    type Output = T;
+
+    /// Indexed by `(row, col)`
+    fn index(&self, (row, col): (usize, usize)) -> &Self::Output {
+        assert!(col < self.n_cols, "Column {col} out of bounds");
+        &self.values[row * self.n_cols + col]
+    }
+}
+
+impl<T> IndexMut<(usize, usize)> for Matrix<T> {
+    fn index_mut(&mut self, (row, col): (usize, usize)) -> &mut Self::Output {
+        assert!(col < self.n_cols, "Column {col} out of bounds");
+        &mut self.values[row * self.n_cols + col]
+    }
+}
+
+/// Panics if the shapes differ.
+impl<T> Add for &Matrix<T>
+where
+    T: Clone + Zero + One,
+{
+    type Output = Matrix<T>;
+
+    fn add(self, rhs: Self) -> Self::Output {
+        assert_eq!(
+            (self.n_rows, self.n_cols),
+            (rhs.n_rows, rhs.n_cols),
+            "Cannot add matrices of different shapes"
+        );
+
+        Matrix {
+            values: self
+                .values
+                .iter()
+                .zip(&rhs.values)
+                .map(|(a, b)| a.clone() + b.clone())
+                .collect(),
+            ..self.clone()
+        }
+    }
+}
+
+/// Panics if `self` does not have one column per row of `rhs`.
+impl<T> Mul for &Matrix<T>
+where
+    T: Clone + Zero + One,
+{
+    type Output = Matrix<T>;
+
+    fn mul(self, rhs: Self) -> Self::Output {
+        assert_eq!(
+            self.n_cols, rhs.n_rows,
+            "Cannot multiply {}x{} matrix by {}x{} matrix",
+            self.n_rows, self.n_cols, rhs.n_rows, rhs.n_cols
+        );
+
+        let mut result = Matrix::zeros(self.n_rows, rhs.n_cols);
+        for row in 0..self.n_rows {
+            for col in 0..rhs.n_cols {
+                let column = (0..rhs.n_rows).map(|k| &rhs[(k, col)]);
+                result[(row, col)] = dot(self.row(row), column);
+            }
+        }
+
+        result
+    }
+}
+
+/// One row per line, e.g. `[1, 2]\n[3, 4]`
+impl<T> Display for Matrix<T>
+where
+    T: Display,
+{
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        for row in 0..self.n_rows {
+            if row > 0 {
+                writeln!(f)?;
+            }
+
+            write!(f, "[")?;
+            for col in 0..self.n_cols {
+                if col > 0 {
+                    write!(f, ", ")?;
+                }
+                write!(f, "{}", self.values[row * self.n_cols + col])?;
+            }
+            write!(f, "]")?;
+        }
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::math::Rational;
+
+    fn matrix(rows: &[&[i64]]) -> Matrix<i64> {
+        Matrix::from_rows(rows.iter().map(|row| row.to_vec()).collect()).unwrap()
+    }
+
+    #[test]
+    fn test_products() {
+        let a = matrix(&[&[1, 2, 3], &[4, 5, 6]]);
+        let b = matrix(&[&[7, 8], &[9, 10], &[11, 12]]);
+
+        assert_eq!(matrix(&[&[58, 64], &[139, 154]]), &a * &b);
+        assert_eq!(matrix(&[&[1, 4], &[2, 5], &[3, 6]]), a.transpose());
+        assert_eq!(a, &Matrix::identity(2) * &a);
+        assert_eq!(a, &a * &Matrix::identity(3));
+        assert_eq!(vec![14, 32], a.mul_vector(&[1, 2, 3]));
+        assert_eq!(matrix(&[&[2, 4, 6], &[8, 10, 12]]), &a + &a);
+        assert_eq!("[1, 2, 3]\n[4, 5, 6]", a.to_string());
+
+        assert!(Matrix::from_rows(vec![vec![1], vec![1, 2]]).is_err());
+    }
+
+    #[test]
+    fn test_pow() {
+        // [[1, 1], [1, 0]]^n = [[F(n+1), F(n)], [F(n), F(n-1)]]
+        let fibonacci = Matrix::from_rows(vec![vec![1_i128, 1], vec![1, 0]]).unwrap();
+        assert_eq!(Matrix::identity(2), fibonacci.pow(0));
+        assert_eq!(55, fibonacci.pow(10)[(0, 1)]);
+        assert_eq!(354_224_848_179_261_915_075, fibonacci.pow(100)[(0, 1)]);
+        assert_eq!(Some(fibonacci.pow(100)), fibonacci.checked_pow(100));
+        // F(185) is beyond i128, and appears first in the 184th power.
+        assert!(fibonacci.checked_pow(183).is_some());
+        assert_eq!(None, fibonacci.checked_pow(184));
+        assert_eq!(None, fibonacci.checked_mul_vector(&[i128::MAX, 1]));
+
+        let half = Rational::new(1, 2).unwrap();
+        let scale =
+            Matrix::from_rows(vec![vec![half, Rational::ZERO], vec![Rational::ONE, half]]).unwrap();
+        let cubed = scale.pow(3);
+        assert_eq!(Rational::new(1, 8).unwrap(), cubed[(0, 0)]);
+        assert_eq!(&(&scale * &scale) * &scale, cubed);
+    }
+
+    #[test]
+    fn test_float_and_grid() {
+        // Rotation by a quarter turn, four times over
+        let rotation = Matrix::from_rows(vec![vec![0.0, -1.0], vec![1.0, 0.0]]).unwrap();
+        assert_eq!(vec![-2.0, 1.0], rotation.mul_vector(&[1.0, 2.0]));
+        assert_eq!(Matrix::identity(2), rotation.pow(4));
+
+        let grid = rotation.to_grid();
+        assert_eq!(2, grid.n_rows());
+        assert_eq!(rotation, Matrix::from_grid(&grid));
+    }
+}
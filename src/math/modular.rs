@@ -0,0 +1,355 @@
+use crate::math::extended_euclidean;
+use prime_factorization::Factorization;
+use std::fmt::{Display, Formatter};
+use std::ops::{Add, Div, Mul, Neg, Sub};
+
+/// `(a + b) % modulus` without overflowing, for `a` and `b` already reduced.
+pub fn addmod(a: u64, b: u64, modulus: u64) -> u64 {
+    ((a as u128 + b as u128) % modulus as u128) as u64
+}
+
+/// `(a - b) % modulus`, always non-negative, for `a` and `b` already reduced.
+pub fn submod(a: u64, b: u64, modulus: u64) -> u64 {
+    if a >= b {
+        a - b
+    } else {
+        modulus - (b - a)
+    }
+}
+
+/// `(a * b) % modulus` without overflowing, for any 64-bit values.
+pub fn mulmod(a: u64, b: u64, modulus: u64) -> u64 {
+    ((a as u128 * b as u128) % modulus as u128) as u64
+}
+
+/// `base^exp % modulus`, by repeated squaring.
+pub fn powmod(base: u64, mut exp: u64, modulus: u64) -> u64 {
+    let mut result = 1 % modulus;
+    let mut base = base % modulus;
+    while exp > 0 {
+        if exp & 1 == 1 {
+            result = mulmod(result, base, modulus);
+        }
+        base = mulmod(base, base, modulus);
+        exp >>= 1;
+    }
+
+    result
+}
+
+/// The `x` with `a * x = 1 (mod modulus)`.
+/// Returns `None` if `a` and `modulus` are not coprime, so no such value exists.
+pub fn mod_inverse(a: u64, modulus: u64) -> Option<u64> {
+    let (gcd, m, _) = extended_euclidean((a % modulus) as i128, modulus as i128);
+    (gcd == 1).then(|| m.rem_euclid(modulus as i128) as u64)
+}
+
+/// An integer modulo a modulus chosen at runtime.
+/// Values are always reduced to `0..modulus`.
+///
+/// Combining values with different moduli panics.
+#[derive(Debug, Copy, Clone, Eq, PartialEq, Hash)]
+pub struct ModInt {
+    value: u64,
+    modulus: u64,
+}
+
+#[allow(dead_code)]
+impl ModInt {
+    /// Panics if `modulus` is zero.
+    pub fn new(value: u64, modulus: u64) -> Self {
+        assert!(modulus > 0, "Modulus must be positive");
+        Self {
+            value: value % modulus,
+            modulus,
+        }
+    }
+
+    /// Panics if `modulus` is zero.
+    pub fn from_signed(value: i64, modulus: u64) -> Self {
+        assert!(modulus > 0, "Modulus must be positive");
+        Self {
+            value: (value as i128).rem_euclid(modulus as i128) as u64,
+            modulus,
+        }
+    }
+
+    pub fn value(self) -> u64 {
+        self.value
+    }
+
+    pub fn modulus(self) -> u64 {
+        self.modulus
+    }
+
+    pub fn pow(self, exp: u64) -> Self {
+        Self {
+            value: powmod(self.value, exp, self.modulus),
+            ..self
+        }
+    }
+
+    /// Returns `None` if the value shares a factor with the modulus.
+    pub fn inverse(self) -> Option<Self> {
+        Some(Self {
+            value: mod_inverse(self.value, self.modulus)?,
+            ..self
+        })
+    }
+
+    /// Whether `self^n = 1`.
+    pub fn is_root_of_unity(self, n: u64) -> bool {
+        self.pow(n).value == 1 % self.modulus
+    }
+
+    /// Whether `n` is the smallest positive power for which `self^n = 1`.
+    pub fn is_primitive_root_of_unity(self, n: u64) -> bool {
+        if n == 0 || !self.is_root_of_unity(n) {
+            return false;
+        }
+
+        // The order divides n, so it is n unless it also divides some n / p.
+        let mut prime_factors = Factorization::run(n).factors;
+        prime_factors.dedup();
+        prime_factors
+            .into_iter()
+            .all(|p| !self.is_root_of_unity(n / p))
+    }
+
+    fn check_modulus(self, other: Self) {
+        assert_eq!(
+            self.modulus, other.modulus,
+            "Cannot combine values with different moduli"
+        );
+    }
+}
+
+impl Add for ModInt {
+    type Output = Self;
+
+    fn add(self, rhs: Self) -> Self::Output {
+        self.check_modulus(rhs);
+        Self {
+            value: addmod(self.value, rhs.value, self.modulus),
+            ..self
+        }
+    }
+}
+
+impl Sub for ModInt {
+    type Output = Self;
+
+    fn sub(self, rhs: Self) -> Self::Output {
+        self.check_modulus(rhs);
+        Self {
+            value: submod(self.value, rhs.value, self.modulus),
+            ..self
+        }
+    }
+}
+
+impl Neg for ModInt {
+    type Output = Self;
+
+    fn neg(self) -> Self::Output {
+        Self::new(0, self.modulus) - self
+    }
+}
+
+impl Mul for ModInt {
+    type Output = Self;
+
+    fn mul(self, rhs: Self) -> Self::Output {
+        self.check_modulus(rhs);
+        Self {
+            value: mulmod(self.value, rhs.value, self.modulus),
+            ..self
+        }
+    }
+}
+
+/// Panics if `rhs` has no inverse.
+impl Div for ModInt {
+    type Output = Self;
+
+    fn div(self, rhs: Self) -> Self::Output {
+        self.check_modulus(rhs);
+        let inverse = rhs
+            .inverse()
+            .expect("Divisor is not invertible for this modulus");
+        Self {
+            value: mulmod(self.value, inverse.value, self.modulus),
+            ..self
+        }
+    }
+}
+
+impl Display for ModInt {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{} (mod {})", self.value, self.modulus)
+    }
+}
+
+/// An integer modulo `M`, which is fixed at compile time.
+/// Values are always reduced to `0..M`.
+#[derive(Debug, Copy, Clone, Eq, PartialEq, Hash)]
+pub struct Mod<const M: u64> {
+    value: u64,
+}
+
+#[allow(dead_code)]
+impl<const M: u64> Mod<M> {
+    /// Panics if `M` is zero.
+    pub fn new(value: u64) -> Self {
+        Self::from(ModInt::new(value, M))
+    }
+
+    pub fn from_signed(value: i64) -> Self {
+        Self::from(ModInt::from_signed(value, M))
+    }
+
+    pub fn value(self) -> u64 {
+        self.value
+    }
+
+    /// The same value with the modulus carried at runtime.
+    pub fn to_runtime(self) -> ModInt {
+        ModInt {
+            value: self.value,
+            modulus: M,
+        }
+    }
+
+    pub fn pow(self, exp: u64) -> Self {
+        Self::from(self.to_runtime().pow(exp))
+    }
+
+    /// Returns `None` if the value shares a factor with `M`.
+    pub fn inverse(self) -> Option<Self> {
+        self.to_runtime().inverse().map(Self::from)
+    }
+
+    pub fn is_root_of_unity(self, n: u64) -> bool {
+        self.to_runtime().is_root_of_unity(n)
+    }
+
+    pub fn is_primitive_root_of_unity(self, n: u64) -> bool {
+        self.to_runtime().is_primitive_root_of_unity(n)
+    }
+}
+
+/// Only for values whose modulus is `M`.
+impl<const M: u64> From<ModInt> for Mod<M> {
+    fn from(value: ModInt) -> Self {
+        debug_assert_eq!(M, value.modulus);
+        Self { value: value.value }
+    }
+}
+
+impl<const M: u64> Add for Mod<M> {
+    type Output = Self;
+
+    fn add(self, rhs: Self) -> Self::Output {
+        Self::from(self.to_runtime() + rhs.to_runtime())
+    }
+}
+
+impl<const M: u64> Sub for Mod<M> {
+    type Output = Self;
+
+    fn sub(self, rhs: Self) -> Self::Output {
+        Self::from(self.to_runtime() - rhs.to_runtime())
+    }
+}
+
+impl<const M: u64> Neg for Mod<M> {
+    type Output = Self;
+
+    fn neg(self) -> Self::Output {
+        Self::from(-self.to_runtime())
+    }
+}
+
+impl<const M: u64> Mul for Mod<M> {
+    type Output = Self;
+
+    fn mul(self, rhs: Self) -> Self::Output {
+        Self::from(self.to_runtime() * rhs.to_runtime())
+    }
+}
+
+/// Panics if `rhs` has no inverse.
+impl<const M: u64> Div for Mod<M> {
+    type Output = Self;
+
+    fn div(self, rhs: Self) -> Self::Output {
+        Self::from(self.to_runtime() / rhs.to_runtime())
+    }
+}
+
+impl<const M: u64> Display for Mod<M> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        self.to_runtime().fmt(f)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_large_modulus() {
+        // Products of values this size overflow u64.
+        let m = u64::MAX - 58; // The largest 64-bit prime
+        let a = m - 1;
+        assert_eq!(1, mulmod(a, a, m));
+        assert_eq!(m - 2, addmod(a, a, m));
+        assert_eq!(2, submod(1, a, m));
+        assert_eq!(1, powmod(a, 1_000_001 * 2, m));
+
+        // Fermat's little theorem
+        assert_eq!(1, powmod(123_456_789, m - 1, m));
+        assert_eq!(0, powmod(5, 0, 1));
+    }
+
+    #[test]
+    fn test_inverse_and_division() {
+        assert_eq!(Some(4), mod_inverse(3, 11));
+        assert_eq!(None, mod_inverse(6, 9));
+
+        let a = ModInt::new(7, 13);
+        let b = ModInt::from_signed(-3, 13);
+        assert_eq!(10, b.value());
+        assert_eq!(a, (a / b) * b);
+        assert_eq!(ModInt::new(4, 13), a + b);
+        assert_eq!(ModInt::new(10, 13), b - a + a);
+        assert_eq!(ModInt::new(6, 13), -a);
+        assert_eq!(None, ModInt::new(4, 8).inverse());
+
+        type F = Mod<1_000_000_007>;
+        let x = F::new(2);
+        assert_eq!(F::new(500_000_004), x.inverse().unwrap());
+        assert_eq!(F::new(1), x * (F::new(1) / x));
+        assert_eq!(F::from_signed(-1), F::new(1_000_000_006));
+        assert_eq!("1024 (mod 1000000007)", x.pow(10).to_string());
+    }
+
+    #[test]
+    fn test_roots_of_unity() {
+        // 2 has order 10 modulo 11, while 4 = 2^2 has order 5.
+        let two = Mod::<11>::new(2);
+        let four = Mod::<11>::new(4);
+        assert!(two.is_primitive_root_of_unity(10));
+        assert!(four.is_root_of_unity(10));
+        assert!(!four.is_primitive_root_of_unity(10));
+        assert!(four.is_primitive_root_of_unity(5));
+        assert!(!two.is_root_of_unity(5));
+        assert!(!two.is_primitive_root_of_unity(0));
+    }
+
+    #[should_panic]
+    #[test]
+    fn test_mismatched_moduli() {
+        let _ = ModInt::new(1, 5) + ModInt::new(1, 7);
+    }
+}
@@ -0,0 +1,371 @@
+use crate::math::Rational;
+use crate::AdventErr::Compute;
+use crate::AdventResult;
+use num::{One, Zero};
+use std::fmt::{Display, Formatter};
+use std::ops::{Add, Mul, Neg, Sub};
+
+/// A polynomial in one variable, stored as coefficients indexed by power.
+/// Trailing zero coefficients are never stored, so equal polynomials compare equal.
+#[derive(Debug, Clone, Eq, PartialEq)]
+pub struct Polynomial<T> {
+    coefficients: Vec<T>,
+}
+
+#[allow(dead_code)]
+impl<T> Polynomial<T>
+where
+    T: Clone + Zero + One,
+{
+    /// `coefficients[i]` is the coefficient of x^i.
+    pub fn new(mut coefficients: Vec<T>) -> Self {
+        while coefficients.last().is_some_and(|c| c.is_zero()) {
+            coefficients.pop();
+        }
+
+        Self { coefficients }
+    }
+
+    pub fn zero() -> Self {
+        Self {
+            coefficients: vec![],
+        }
+    }
+
+    pub fn constant(value: T) -> Self {
+        Self::new(vec![value])
+    }
+
+    /// The polynomial `x`
+    pub fn x() -> Self {
+        Self::new(vec![T::zero(), T::one()])
+    }
+
+    pub fn coefficients(&self) -> &[T] {
+        &self.coefficients
+    }
+
+    /// `None` for the zero polynomial.
+    pub fn degree(&self) -> Option<usize> {
+        self.coefficients.len().checked_sub(1)
+    }
+
+    pub fn evaluate(&self, x: T) -> T {
+        self.coefficients
+            .iter()
+            .rev()
+            .fold(T::zero(), |acc, coefficient| {
+                acc * x.clone() + coefficient.clone()
+            })
+    }
+
+    /// The polynomial `self(inner(x))`
+    pub fn compose(&self, inner: &Self) -> Self {
+        self.coefficients
+            .iter()
+            .rev()
+            .fold(Self::zero(), |acc, coefficient| {
+                &(&acc * inner) + &Self::constant(coefficient.clone())
+            })
+    }
+}
+
+#[allow(dead_code)]
+impl Polynomial<Rational> {
+    /// The unique polynomial of lowest degree passing through every point.
+    /// Fails if two points share an x coordinate.
+    pub fn lagrange(points: &[(Rational, Rational)]) -> AdventResult<Self> {
+        let mut result = Self::zero();
+
+        for (i, &(x_i, y_i)) in points.iter().enumerate() {
+            let mut basis = Self::constant(y_i);
+            for (j, &(x_j, _)) in points.iter().enumerate() {
+                if i == j {
+                    continue;
+                }
+
+                let Some(scale) = (x_i - x_j).recip() else {
+                    return Err(Compute(format!(
+                        "Cannot interpolate: x = {x_i} appears more than once"
+                    )));
+                };
+
+                // Multiply by (x - x_j) / (x_i - x_j)
+                basis = &basis * &Self::new(vec![-x_j * scale, scale]);
+            }
+
+            result = &result + &basis;
+        }
+
+        Ok(result)
+    }
+
+    /// The polynomial of lowest degree with `p(i) = values[i]`,
+    /// built from the forward differences of `values`.
+    /// Only the differences up to the detected degree are used,
+    /// so any further values only need to be consistent with it.
+    pub fn newton_forward(values: &[i64]) -> AdventResult<Self> {
+        let Some(degree) = sequence_degree(values) else {
+            return Err(Compute(format!(
+                "Failed to find polynomial degree for {values:?}"
+            )));
+        };
+
+        let mut result = Self::zero();
+        // x (x - 1) ... (x - k + 1) / k!
+        let mut falling = Self::constant(Rational::ONE);
+        for (k, difference) in forward_differences(values)?
+            .into_iter()
+            .take(degree + 1)
+            .enumerate()
+        {
+            result = &result + &(&falling * &Self::constant(Rational::from(difference)));
+
+            // Multiply by (x - k) / (k + 1)
+            let k = Rational::from(k as i64);
+            let scale = Rational::ONE / (k + Rational::ONE);
+            falling = &falling * &Self::new(vec![-k * scale, scale]);
+        }
+
+        Ok(result)
+    }
+
+    /// As `evaluate`, but fails rather than panicking if the arithmetic overflows.
+    pub fn checked_evaluate(&self, x: Rational) -> AdventResult<Rational> {
+        self.coefficients
+            .iter()
+            .rev()
+            .try_fold(Rational::ZERO, |acc, &coefficient| {
+                acc.checked_mul(x)?.checked_add(coefficient)
+            })
+            .ok_or_else(|| Compute(format!("Overflow evaluating {self} at {x}")))
+    }
+
+    /// Evaluates at an integer point, requiring a whole number result.
+    pub fn evaluate_integer(&self, x: i64) -> AdventResult<i64> {
+        let value = self.checked_evaluate(Rational::from(x))?;
+        value
+            .to_integer()
+            .and_then(|value| i64::try_from(value).ok())
+            .ok_or_else(|| Compute(format!("Polynomial value at {x} is not an i64: {value}")))
+    }
+}
+
+/// The first element of each row of the forward difference table.
+/// `result[k]` is the k-th difference at 0.
+fn forward_differences(values: &[i64]) -> AdventResult<Vec<i64>> {
+    let mut row = values.to_vec();
+    let mut result = vec![];
+    while let Some(&first) = row.first() {
+        result.push(first);
+        row = differences(&row)
+            .ok_or_else(|| Compute(format!("Overflow taking forward differences of {values:?}")))?;
+    }
+
+    Ok(result)
+}
+
+/// The differences between consecutive values, or `None` on overflow.
+fn differences(row: &[i64]) -> Option<Vec<i64>> {
+    row.windows(2)
+        .map(|pair| pair[1].checked_sub(pair[0]))
+        .collect()
+}
+
+/// The degree of the lowest degree polynomial generating `values` at `0, 1, 2, ...`,
+/// or `None` if there are not enough values to be sure, or the differences overflow.
+/// A sequence of zeroes is reported as degree 0.
+pub fn sequence_degree(values: &[i64]) -> Option<usize> {
+    let mut row = values.to_vec();
+    let mut degree = 0;
+
+    loop {
+        if row.len() < 2 {
+            // A constant needs at least two values to be recognised.
+            return None;
+        }
+
+        if row.iter().all(|&x| x == row[0]) {
+            return Some(degree);
+        }
+
+        row = differences(&row)?;
+        degree += 1;
+    }
+}
+
+impl<T> Add for &Polynomial<T>
+where
+    T: Clone + Zero + One,
+{
+    type Output = Polynomial<T>;
+
+    fn add(self, rhs: Self) -> Self::Output {
+        let len = self.coefficients.len().max(rhs.coefficients.len());
+        let coefficient =
+            |poly: &Polynomial<T>, i| poly.coefficients.get(i).cloned().unwrap_or_else(T::zero);
+
+        Polynomial::new(
+            (0..len)
+                .map(|i| coefficient(self, i) + coefficient(rhs, i))
+                .collect(),
+        )
+    }
+}
+
+impl<T> Neg for &Polynomial<T>
+where
+    T: Clone + Zero + One + Neg<Output = T>,
+{
+    type Output = Polynomial<T>;
+
+    fn neg(self) -> Self::Output {
+        Polynomial::new(self.coefficients.iter().cloned().map(T::neg).collect())
+    }
+}
+
+impl<T> Sub for &Polynomial<T>
+where
+    T: Clone + Zero + One + Neg<Output = T>,
+{
+    type Output = Polynomial<T>;
+
+    fn sub(self, rhs: Self) -> Self::Output {
+        self + &(-rhs)
+    }
+}
+
+impl<T> Mul for &Polynomial<T>
+where
+    T: Clone + Zero + One,
+{
+    type Output = Polynomial<T>;
+
+    fn mul(self, rhs: Self) -> Self::Output {
+        if self.coefficients.is_empty() || rhs.coefficients.is_empty() {
+            return Polynomial::zero();
+        }
+
+        let mut coefficients =
+            vec![T::zero(); self.coefficients.len() + rhs.coefficients.len() - 1];
+        for (i, a) in self.coefficients.iter().enumerate() {
+            for (j, b) in rhs.coefficients.iter().enumerate() {
+                coefficients[i + j] = coefficients[i + j].clone() + a.clone() * b.clone();
+            }
+        }
+
+        Polynomial::new(coefficients)
+    }
+}
+
+/// Highest power first, e.g. `3x^2 + -1x + 1/2`
+impl<T> Display for Polynomial<T>
+where
+    T: Display,
+{
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        if self.coefficients.is_empty() {
+            return write!(f, "0");
+        }
+
+        for (power, coefficient) in self.coefficients.iter().enumerate().rev() {
+            if power + 1 < self.coefficients.len() {
+                write!(f, " + ")?;
+            }
+
+            match power {
+                0 => write!(f, "{coefficient}")?,
+                1 => write!(f, "{coefficient}x")?,
+                _ => write!(f, "{coefficient}x^{power}")?,
+            }
+        }
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn r(value: i64) -> Rational {
+        Rational::from(value)
+    }
+
+    #[test]
+    fn test_sequence_degree() {
+        let cases = [
+            (vec![0, 0, 0, 0], 0),
+            (vec![5, 5], 0),
+            (vec![1, 2, 3], 1),
+            (vec![1, 2, 3, 4, 5], 1),
+            (vec![1, 2, 4, 7], 2),
+            (vec![1, 2, 4, 7, 11], 2),
+            (vec![10, 13, 16, 21, 30, 45], 3),
+        ];
+
+        for (sequence, expected) in cases {
+            assert_eq!(
+                Some(expected),
+                sequence_degree(&sequence),
+                "sequence_degree({:?})",
+                &sequence
+            );
+        }
+
+        assert_eq!(None, sequence_degree(&[]));
+        assert_eq!(None, sequence_degree(&[1, 2]));
+    }
+
+    #[test]
+    fn test_interpolation() {
+        let values = [10, 13, 16, 21, 30, 45];
+        let newton = Polynomial::newton_forward(&values).unwrap();
+        assert_eq!(Some(3), newton.degree());
+        assert_eq!(68, newton.evaluate_integer(6).unwrap());
+        assert_eq!(5, newton.evaluate_integer(-1).unwrap());
+
+        let points: Vec<_> = values
+            .iter()
+            .enumerate()
+            .take(4)
+            .map(|(x, &y)| (r(x as i64), r(y)))
+            .collect();
+        assert_eq!(newton, Polynomial::lagrange(&points).unwrap());
+
+        assert!(Polynomial::lagrange(&[(r(1), r(1)), (r(1), r(2))]).is_err());
+        assert!(Polynomial::newton_forward(&[1]).is_err());
+    }
+
+    #[test]
+    fn test_overflow() {
+        let steep = Polynomial::new(vec![r(0), r(i64::MAX)]);
+        assert_eq!(i64::MAX, steep.evaluate_integer(1).unwrap());
+        assert!(steep.evaluate_integer(2).is_err());
+
+        let cube = Polynomial::new(vec![r(0), r(0), r(0), r(1)]);
+        assert!(cube.checked_evaluate(r(i64::MAX)).is_err());
+
+        assert_eq!(None, sequence_degree(&[i64::MIN, i64::MAX, 0]));
+        assert!(forward_differences(&[i64::MIN, i64::MAX]).is_err());
+        assert!(Polynomial::newton_forward(&[i64::MIN, i64::MAX, 0]).is_err());
+    }
+
+    #[test]
+    fn test_arithmetic() {
+        // (x + 1) and (x - 1)
+        let a = Polynomial::new(vec![1_i64, 1]);
+        let b = Polynomial::new(vec![-1_i64, 1]);
+
+        assert_eq!(Polynomial::new(vec![-1, 0, 1]), &a * &b);
+        assert_eq!(Polynomial::new(vec![0, 2]), &a + &b);
+        assert_eq!(Polynomial::constant(2), &a - &b);
+        assert_eq!(None, (&a - &a).degree());
+
+        // (x - 1)^2 + 1 composed with (x + 1) is x^2 + 1
+        let square_plus_one = &(&b * &b) + &Polynomial::constant(1);
+        assert_eq!(Polynomial::new(vec![1, 0, 1]), square_plus_one.compose(&a));
+        assert_eq!(10, square_plus_one.evaluate(4));
+        assert_eq!("1x^2 + -2x + 2", square_plus_one.to_string());
+    }
+}
@@ -0,0 +1,327 @@
+use crate::AdventErr::Compute;
+use crate::{AdventErr, AdventResult};
+use std::fmt::{Display, Formatter};
+
+/// The largest `r` with `r * r <= n`.
+pub fn isqrt_u128(n: u128) -> u128 {
+    if n < 2 {
+        return n;
+    }
+
+    // Start at a power of two no smaller than the root.
+    // From there, Newton's method decreases steadily until it reaches the floor of the root.
+    let bits = u128::BITS - n.leading_zeros();
+    let mut x = 1_u128 << bits.div_ceil(2);
+    loop {
+        let next = (x + n / x) / 2;
+        if next >= x {
+            return x;
+        }
+        x = next;
+    }
+}
+
+/// The largest `r` with `r * r <= n`.
+#[allow(dead_code)]
+pub fn isqrt_u64(n: u64) -> u64 {
+    isqrt_u128(n as u128) as u64
+}
+
+/// Returns `Some` only if `n` is a perfect square.
+#[allow(dead_code)]
+pub fn exact_sqrt(n: u128) -> Option<u128> {
+    let root = isqrt_u128(n);
+    (root * root == n).then_some(root)
+}
+
+#[derive(Debug, Copy, Clone, Eq, PartialEq)]
+pub enum Inequality {
+    Greater,
+    GreaterOrEqual,
+    Less,
+    LessOrEqual,
+}
+
+impl Inequality {
+    /// The inequality after multiplying both sides by -1.
+    fn flip(self) -> Self {
+        match self {
+            Inequality::Greater => Inequality::Less,
+            Inequality::GreaterOrEqual => Inequality::LessOrEqual,
+            Inequality::Less => Inequality::Greater,
+            Inequality::LessOrEqual => Inequality::GreaterOrEqual,
+        }
+    }
+
+    fn holds(self, lhs: i128, rhs: i128) -> bool {
+        match self {
+            Inequality::Greater => lhs > rhs,
+            Inequality::GreaterOrEqual => lhs >= rhs,
+            Inequality::Less => lhs < rhs,
+            Inequality::LessOrEqual => lhs <= rhs,
+        }
+    }
+}
+
+/// The integers from `min` to `max` inclusive.
+/// A bound of `None` means the range is unbounded in that direction.
+#[derive(Debug, Copy, Clone, Eq, PartialEq)]
+pub struct IntegerRange {
+    pub min: Option<i128>,
+    pub max: Option<i128>,
+}
+
+#[allow(dead_code)]
+impl IntegerRange {
+    pub const ALL: Self = Self {
+        min: None,
+        max: None,
+    };
+
+    /// Returns `None` if the range would be empty.
+    fn bounded(min: i128, max: i128) -> Option<Self> {
+        (min <= max).then_some(Self {
+            min: Some(min),
+            max: Some(max),
+        })
+    }
+
+    pub fn contains(self, value: i128) -> bool {
+        self.min.is_none_or(|min| min <= value) && self.max.is_none_or(|max| value <= max)
+    }
+
+    /// The number of integers in the range, or `None` if it is unbounded.
+    pub fn len(self) -> Option<u128> {
+        Some(self.min?.abs_diff(self.max?) + 1)
+    }
+
+    /// The number of integers which are in both the range and `low..=high`.
+    pub fn count_within(self, low: i128, high: i128) -> u128 {
+        let low = self.min.map_or(low, |min| min.max(low));
+        let high = self.max.map_or(high, |max| max.min(high));
+        if low > high {
+            0
+        } else {
+            low.abs_diff(high) + 1
+        }
+    }
+}
+
+/// e.g. `[-3, 5]` or `(-inf, 2]`
+impl Display for IntegerRange {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self.min {
+            Some(min) => write!(f, "[{min}, ")?,
+            None => write!(f, "(-inf, ")?,
+        }
+        match self.max {
+            Some(max) => write!(f, "{max}]"),
+            None => write!(f, "inf)"),
+        }
+    }
+}
+
+fn overflow() -> AdventErr {
+    Compute(String::from("Overflow while solving quadratic inequality"))
+}
+
+/// The integers `x` satisfying `a x^2 + b x + c (inequality) 0`, as at most two disjoint ranges,
+/// in increasing order.
+///
+/// No floating point is involved, so the bounds are exact for any inputs.
+/// Fails only if the discriminant does not fit in an i128.
+pub fn solve_quadratic_inequality(
+    a: i64,
+    b: i64,
+    c: i64,
+    inequality: Inequality,
+) -> AdventResult<Vec<IntegerRange>> {
+    let (a, b, c) = (a as i128, b as i128, c as i128);
+
+    if a == 0 {
+        return Ok(solve_linear_inequality(b, c, inequality));
+    }
+
+    // Make the leading coefficient positive, so the parabola opens upwards.
+    let (a, b, c, inequality) = if a < 0 {
+        (-a, -b, -c, inequality.flip())
+    } else {
+        (a, b, c, inequality)
+    };
+
+    // 4a f(x) = (2ax + b)^2 - D, so the sign of f(x) depends only on how |2ax + b| compares to √D.
+    let discriminant = b
+        .checked_mul(b)
+        .zip(a.checked_mul(c).and_then(|ac| ac.checked_mul(4)))
+        .and_then(|(b2, ac4)| b2.checked_sub(ac4))
+        .ok_or_else(overflow)?;
+
+    if discriminant < 0 {
+        // f(x) is always positive
+        return Ok(if inequality.holds(1, 0) {
+            vec![IntegerRange::ALL]
+        } else {
+            vec![]
+        });
+    }
+
+    let root = isqrt_u128(discriminant as u128) as i128;
+    // The largest |2ax + b| for which f(x) <= 0, or for which f(x) < 0.
+    let non_positive_limit = root;
+    let negative_limit = if root * root == discriminant {
+        root - 1
+    } else {
+        root
+    };
+
+    // Values of x where -limit <= 2ax + b <= limit
+    let within = |limit: i128| {
+        if limit < 0 {
+            return None;
+        }
+        let two_a = 2 * a;
+        IntegerRange::bounded(
+            (-limit - b).div_euclid(two_a) + i128::from((-limit - b).rem_euclid(two_a) != 0),
+            (limit - b).div_euclid(two_a),
+        )
+    };
+
+    Ok(match inequality {
+        Inequality::Less => within(negative_limit).into_iter().collect(),
+        Inequality::LessOrEqual => within(non_positive_limit).into_iter().collect(),
+        Inequality::Greater => complement(within(non_positive_limit)),
+        Inequality::GreaterOrEqual => complement(within(negative_limit)),
+    })
+}
+
+/// Integers outside a bounded range.
+fn complement(range: Option<IntegerRange>) -> Vec<IntegerRange> {
+    let Some(IntegerRange {
+        min: Some(min),
+        max: Some(max),
+    }) = range
+    else {
+        return vec![IntegerRange::ALL];
+    };
+
+    vec![
+        IntegerRange {
+            min: None,
+            max: Some(min - 1),
+        },
+        IntegerRange {
+            min: Some(max + 1),
+            max: None,
+        },
+    ]
+}
+
+/// The integers `x` satisfying `b x + c (inequality) 0`
+fn solve_linear_inequality(b: i128, c: i128, inequality: Inequality) -> Vec<IntegerRange> {
+    if b == 0 {
+        return if inequality.holds(c, 0) {
+            vec![IntegerRange::ALL]
+        } else {
+            vec![]
+        };
+    }
+
+    let (b, c, inequality) = if b < 0 {
+        (-b, -c, inequality.flip())
+    } else {
+        (b, c, inequality)
+    };
+
+    // x compared to -c / b
+    let floor = (-c).div_euclid(b);
+    let ceil = floor + i128::from((-c).rem_euclid(b) != 0);
+
+    let range = match inequality {
+        Inequality::Greater => IntegerRange {
+            min: Some(floor + 1),
+            max: None,
+        },
+        Inequality::GreaterOrEqual => IntegerRange {
+            min: Some(ceil),
+            max: None,
+        },
+        Inequality::Less => IntegerRange {
+            min: None,
+            max: Some(ceil - 1),
+        },
+        Inequality::LessOrEqual => IntegerRange {
+            min: None,
+            max: Some(floor),
+        },
+    };
+
+    vec![range]
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_isqrt() {
+        for n in 0..10_000_u128 {
+            let root = isqrt_u128(n);
+            assert!(
+                root * root <= n && (root + 1) * (root + 1) > n,
+                "isqrt({n})"
+            );
+        }
+
+        assert_eq!(u32::MAX as u64, isqrt_u64(u64::MAX));
+        assert_eq!(u64::MAX as u128, isqrt_u128(u128::MAX));
+
+        // f64 gets this wrong: (2^53 + 1)^2 - 1 is just below a perfect square.
+        let big = (1_u128 << 53) + 1;
+        assert_eq!(big - 1, isqrt_u128(big * big - 1));
+        assert_eq!(Some(big), exact_sqrt(big * big));
+        assert_eq!(None, exact_sqrt(big * big + 1));
+    }
+
+    #[test]
+    fn test_quadratic_against_brute_force() {
+        let inequalities = [
+            Inequality::Greater,
+            Inequality::GreaterOrEqual,
+            Inequality::Less,
+            Inequality::LessOrEqual,
+        ];
+
+        for a in -3..=3 {
+            for b in -6..=6 {
+                for c in -9..=9 {
+                    for inequality in inequalities {
+                        let ranges = solve_quadratic_inequality(a, b, c, inequality).unwrap();
+                        for x in -40..=40_i128 {
+                            let value = a as i128 * x * x + b as i128 * x + c as i128;
+                            assert_eq!(
+                                inequality.holds(value, 0),
+                                ranges.iter().any(|range| range.contains(x)),
+                                "{a}x^2 + {b}x + {c} {inequality:?} 0 at x = {x}: {ranges:?}"
+                            );
+                        }
+                    }
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn test_quadratic_ranges() {
+        // x^2 - 4 has roots at -2 and 2.
+        let inside = solve_quadratic_inequality(1, 0, -4, Inequality::Less).unwrap();
+        assert_eq!(vec![IntegerRange::bounded(-1, 1).unwrap()], inside);
+        assert_eq!(Some(3), inside[0].len());
+
+        let outside = solve_quadratic_inequality(1, 0, -4, Inequality::GreaterOrEqual).unwrap();
+        assert_eq!("(-inf, -2]", outside[0].to_string());
+        assert_eq!("[2, inf)", outside[1].to_string());
+        assert_eq!(3, outside[1].count_within(0, 4));
+
+        assert!(solve_quadratic_inequality(i64::MAX, 0, i64::MAX, Inequality::Less).is_err());
+    }
+}
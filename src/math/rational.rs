@@ -0,0 +1,345 @@
+use crate::math::gcd;
+use num::{One, Zero};
+use std::cmp::Ordering;
+use std::fmt::{Display, Formatter};
+use std::ops::{Add, Div, Mul, Neg, Sub};
+
+/// An exact fraction, always kept in lowest terms with a positive denominator.
+///
+/// The `checked_*` methods return `None` if an intermediate value overflows `i128`.
+/// The operator implementations panic in that situation instead.
+#[derive(Debug, Copy, Clone, Eq, PartialEq, Hash)]
+pub struct Rational {
+    numerator: i128,
+    denominator: i128,
+}
+
+#[allow(dead_code)]
+impl Rational {
+    pub const ZERO: Self = Self {
+        numerator: 0,
+        denominator: 1,
+    };
+
+    pub const ONE: Self = Self {
+        numerator: 1,
+        denominator: 1,
+    };
+
+    /// Returns `None` if `denominator` is zero,
+    /// or if the fraction in lowest terms does not fit in `i128`, such as `i128::MIN / -1`.
+    pub fn new(numerator: i128, denominator: i128) -> Option<Self> {
+        if denominator == 0 {
+            return None;
+        }
+
+        // Reduce the magnitudes, since `i128::MIN` has no positive counterpart.
+        let divisor = gcd(numerator.unsigned_abs(), denominator.unsigned_abs());
+        let numerator_magnitude = numerator.unsigned_abs() / divisor;
+        let denominator_magnitude = denominator.unsigned_abs() / divisor;
+
+        let numerator = if (numerator < 0) == (denominator < 0) {
+            i128::try_from(numerator_magnitude).ok()?
+        } else {
+            0_i128.checked_sub_unsigned(numerator_magnitude)?
+        };
+        Some(Self {
+            numerator,
+            denominator: i128::try_from(denominator_magnitude).ok()?,
+        })
+    }
+
+    pub fn from_integer(value: i128) -> Self {
+        Self {
+            numerator: value,
+            denominator: 1,
+        }
+    }
+
+    pub fn numerator(self) -> i128 {
+        self.numerator
+    }
+
+    pub fn denominator(self) -> i128 {
+        self.denominator
+    }
+
+    pub fn is_zero(self) -> bool {
+        self.numerator == 0
+    }
+
+    pub fn is_integer(self) -> bool {
+        self.denominator == 1
+    }
+
+    /// Returns `None` unless the value is a whole number.
+    pub fn to_integer(self) -> Option<i128> {
+        self.is_integer().then_some(self.numerator)
+    }
+
+    pub fn abs(self) -> Self {
+        self.checked_abs()
+            .expect("Rational absolute value overflowed")
+    }
+
+    /// Returns `None` for zero.
+    pub fn recip(self) -> Option<Self> {
+        Self::new(self.denominator, self.numerator)
+    }
+
+    pub fn to_f64(self) -> f64 {
+        self.numerator as f64 / self.denominator as f64
+    }
+
+    pub fn checked_abs(self) -> Option<Self> {
+        Some(Self {
+            numerator: self.numerator.checked_abs()?,
+            ..self
+        })
+    }
+
+    pub fn checked_neg(self) -> Option<Self> {
+        Some(Self {
+            numerator: self.numerator.checked_neg()?,
+            ..self
+        })
+    }
+
+    pub fn checked_add(self, rhs: Self) -> Option<Self> {
+        // Working with the lcm of the denominators keeps intermediate values small.
+        let divisor = gcd(self.denominator, rhs.denominator);
+        let lhs_scale = rhs.denominator / divisor;
+        let rhs_scale = self.denominator / divisor;
+
+        let numerator = self
+            .numerator
+            .checked_mul(lhs_scale)?
+            .checked_add(rhs.numerator.checked_mul(rhs_scale)?)?;
+        let denominator = self.denominator.checked_mul(lhs_scale)?;
+
+        Self::new(numerator, denominator)
+    }
+
+    pub fn checked_sub(self, rhs: Self) -> Option<Self> {
+        self.checked_add(rhs.checked_neg()?)
+    }
+
+    pub fn checked_mul(self, rhs: Self) -> Option<Self> {
+        // Cancel across the two fractions before multiplying.
+        let divisor_a = gcd(self.numerator, rhs.denominator).abs();
+        let divisor_b = gcd(rhs.numerator, self.denominator).abs();
+
+        let numerator = (self.numerator / divisor_a).checked_mul(rhs.numerator / divisor_b)?;
+        let denominator =
+            (self.denominator / divisor_b).checked_mul(rhs.denominator / divisor_a)?;
+
+        Self::new(numerator, denominator)
+    }
+
+    /// Returns `None` on overflow or division by zero.
+    pub fn checked_div(self, rhs: Self) -> Option<Self> {
+        self.checked_mul(rhs.recip()?)
+    }
+}
+
+impl From<i64> for Rational {
+    fn from(value: i64) -> Self {
+        Self::from_integer(value as i128)
+    }
+}
+
+impl Add for Rational {
+    type Output = Self;
+
+    fn add(self, rhs: Self) -> Self::Output {
+        self.checked_add(rhs).expect("Rational addition overflowed")
+    }
+}
+
+impl Sub for Rational {
+    type Output = Self;
+
+    fn sub(self, rhs: Self) -> Self::Output {
+        self.checked_sub(rhs)
+            .expect("Rational subtraction overflowed")
+    }
+}
+
+impl Mul for Rational {
+    type Output = Self;
+
+    fn mul(self, rhs: Self) -> Self::Output {
+        self.checked_mul(rhs)
+            .expect("Rational multiplication overflowed")
+    }
+}
+
+/// Panics on division by zero.
+impl Div for Rational {
+    type Output = Self;
+
+    fn div(self, rhs: Self) -> Self::Output {
+        self.checked_div(rhs)
+            .expect("Rational division overflowed or divided by zero")
+    }
+}
+
+impl Neg for Rational {
+    type Output = Self;
+
+    fn neg(self) -> Self::Output {
+        self.checked_neg().expect("Rational negation overflowed")
+    }
+}
+
+impl Zero for Rational {
+    fn zero() -> Self {
+        Rational::ZERO
+    }
+
+    fn is_zero(&self) -> bool {
+        self.numerator == 0
+    }
+}
+
+impl One for Rational {
+    fn one() -> Self {
+        Rational::ONE
+    }
+}
+
+impl Ord for Rational {
+    fn cmp(&self, other: &Self) -> Ordering {
+        // Denominators are positive, so cross-multiplying preserves the order.
+        match (
+            self.numerator.checked_mul(other.denominator),
+            other.numerator.checked_mul(self.denominator),
+        ) {
+            (Some(lhs), Some(rhs)) => lhs.cmp(&rhs),
+            _ => compare_fractions(
+                (self.numerator, self.denominator),
+                (other.numerator, other.denominator),
+            ),
+        }
+    }
+}
+
+/// Compares `a / b` with `c / d`, for positive `b` and `d`, without overflowing.
+///
+/// Compares the integer parts, then the fractional parts by comparing their reciprocals
+/// the other way round, as when expanding both as continued fractions.
+fn compare_fractions((mut a, mut b): (i128, i128), (mut c, mut d): (i128, i128)) -> Ordering {
+    let mut reversed = false;
+    loop {
+        let ordering = match a.div_euclid(b).cmp(&c.div_euclid(d)) {
+            Ordering::Equal => match (a.rem_euclid(b), c.rem_euclid(d)) {
+                (0, 0) => Ordering::Equal,
+                (0, _) => Ordering::Less,
+                (_, 0) => Ordering::Greater,
+                (lhs_remainder, rhs_remainder) => {
+                    // r/b < s/d exactly when b/r > d/s
+                    (a, b, c, d) = (b, lhs_remainder, d, rhs_remainder);
+                    reversed = !reversed;
+                    continue;
+                }
+            },
+            ordering => ordering,
+        };
+
+        return if reversed {
+            ordering.reverse()
+        } else {
+            ordering
+        };
+    }
+}
+
+impl PartialOrd for Rational {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl Display for Rational {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        if self.is_integer() {
+            write!(f, "{}", self.numerator)
+        } else {
+            write!(f, "{}/{}", self.numerator, self.denominator)
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn r(numerator: i128, denominator: i128) -> Rational {
+        Rational::new(numerator, denominator).unwrap()
+    }
+
+    #[test]
+    fn test_normalization() {
+        assert_eq!(r(1, 2), r(2, 4));
+        assert_eq!(r(-1, 2), r(3, -6));
+        assert_eq!(Rational::ZERO, r(0, -5));
+        assert_eq!(None, Rational::new(1, 0));
+        assert_eq!("-1/2", r(2, -4).to_string());
+        assert_eq!("3", r(6, 2).to_string());
+
+        assert_eq!(Rational::ONE, r(i128::MIN, i128::MIN));
+        assert_eq!(Rational::ZERO, r(0, i128::MIN));
+        assert_eq!(r(1, 2), r(i128::MIN / 2, i128::MIN));
+        assert_eq!(i128::MIN, r(i128::MIN, 1).numerator());
+        assert_eq!(None, Rational::new(i128::MIN, -1));
+        assert_eq!(None, Rational::new(1, i128::MIN));
+    }
+
+    #[test]
+    fn test_arithmetic() {
+        assert_eq!(r(5, 6), r(1, 2) + r(1, 3));
+        assert_eq!(r(1, 6), r(1, 2) - r(1, 3));
+        assert_eq!(r(1, 6), r(1, 2) * r(1, 3));
+        assert_eq!(r(3, 2), r(1, 2) / r(1, 3));
+        assert_eq!(None, r(1, 2).checked_div(Rational::ZERO));
+        assert!(r(1, 3) < r(1, 2));
+        assert!(r(-1, 2) < r(-1, 3));
+    }
+
+    #[test]
+    fn test_ordering_without_overflow() {
+        // These differ by far less than an f64 can resolve, and cross-multiplying overflows.
+        let max = i128::MAX;
+        let cases = [
+            (r(max - 1, max), r(max - 2, max - 1), Ordering::Greater),
+            (r(max - 2, max - 1), r(max - 1, max), Ordering::Less),
+            (r(-(max - 1), max), r(-(max - 2), max - 1), Ordering::Less),
+            (r(max, max - 1), r(max - 1, max - 2), Ordering::Less),
+            (r(max, 2), r(max - 2, 2), Ordering::Greater),
+            (r(max - 1, max), r(max - 1, max), Ordering::Equal),
+            (r(i128::MIN, 3), r(max, 3), Ordering::Less),
+        ];
+
+        for (lhs, rhs, expected) in cases {
+            assert_eq!(expected, lhs.cmp(&rhs), "{lhs} vs {rhs}");
+        }
+    }
+
+    #[test]
+    fn test_overflow_detected() {
+        let big = Rational::from_integer(i128::MAX / 2);
+        assert_eq!(None, big.checked_mul(Rational::from_integer(3)));
+        assert_eq!(None, big.checked_add(big).and_then(|x| x.checked_add(big)));
+
+        let min = Rational::from_integer(i128::MIN);
+        assert_eq!(None, min.checked_abs());
+        assert_eq!(None, min.checked_neg());
+        assert_eq!(Some(r(i128::MAX, 2)), r(-i128::MAX, 2).checked_abs());
+
+        // Cancellation keeps this in range even though the naive product is not.
+        assert_eq!(
+            Some(Rational::ONE),
+            r(i128::MAX, 3).checked_mul(r(3, i128::MAX))
+        );
+    }
+}
@@ -0,0 +1,331 @@
+use crate::data_structures::{Direction, Grid2D, GridPoint2D};
+use std::cmp::min;
+use std::io::{self, IsTerminal, Write};
+
+#[allow(dead_code)]
+#[derive(Debug, Copy, Clone, Eq, PartialEq)]
+pub enum Color {
+    Black,
+    Red,
+    Green,
+    Yellow,
+    Blue,
+    Magenta,
+    Cyan,
+    White,
+    Rgb(u8, u8, u8),
+}
+
+impl Color {
+    fn write_code(self, out: &mut impl Write, foreground: bool) -> io::Result<()> {
+        let base = if foreground { 30 } else { 40 };
+        match self {
+            Color::Black => write!(out, "{}", base),
+            Color::Red => write!(out, "{}", base + 1),
+            Color::Green => write!(out, "{}", base + 2),
+            Color::Yellow => write!(out, "{}", base + 3),
+            Color::Blue => write!(out, "{}", base + 4),
+            Color::Magenta => write!(out, "{}", base + 5),
+            Color::Cyan => write!(out, "{}", base + 6),
+            Color::White => write!(out, "{}", base + 7),
+            Color::Rgb(r, g, b) => write!(out, "{};2;{r};{g};{b}", base + 8),
+        }
+    }
+}
+
+/// A single rendered cell: a character with optional colours.
+#[derive(Debug, Copy, Clone, Eq, PartialEq)]
+pub struct Glyph {
+    pub ch: char,
+    pub fg: Option<Color>,
+    pub bg: Option<Color>,
+}
+
+impl Glyph {
+    pub fn plain(ch: char) -> Self {
+        Self {
+            ch,
+            fg: None,
+            bg: None,
+        }
+    }
+
+    pub fn fg(self, color: Color) -> Self {
+        Self {
+            fg: Some(color),
+            ..self
+        }
+    }
+
+    #[allow(dead_code)]
+    pub fn bg(self, color: Color) -> Self {
+        Self {
+            bg: Some(color),
+            ..self
+        }
+    }
+
+    fn same_style(self, other: Self) -> bool {
+        self.fg == other.fg && self.bg == other.bg
+    }
+}
+
+/// Glyphs drawn over the base grid. Points outside the grid are ignored.
+#[derive(Debug, Clone, Default)]
+pub struct Layer {
+    cells: Vec<(GridPoint2D, Glyph)>,
+}
+
+impl Layer {
+    /// Draws `glyph` at each of `points`.
+    #[allow(dead_code)]
+    pub fn points(points: impl IntoIterator<Item = GridPoint2D>, glyph: Glyph) -> Self {
+        Self {
+            cells: points.into_iter().map(|point| (point, glyph)).collect(),
+        }
+    }
+
+    /// Draws an arrow at each step, pointing in the direction the step was taken.
+    pub fn route(steps: &[(GridPoint2D, Direction)], color: Color) -> Self {
+        Self {
+            cells: steps
+                .iter()
+                .map(|&(point, direction)| {
+                    let arrow = match direction {
+                        Direction::Up => '^',
+                        Direction::Down => 'v',
+                        Direction::Left => '<',
+                        Direction::Right => '>',
+                    };
+                    (point, Glyph::plain(arrow).fg(color))
+                })
+                .collect(),
+        }
+    }
+
+    /// Writes `text` rightwards, starting at `start`.
+    #[allow(dead_code)]
+    pub fn label(start: GridPoint2D, text: &str, color: Color) -> Self {
+        Self {
+            cells: text
+                .chars()
+                .enumerate()
+                .map(|(i, ch)| {
+                    let point = GridPoint2D::new(start.row, start.col + i);
+                    (point, Glyph::plain(ch).fg(color))
+                })
+                .collect(),
+        }
+    }
+}
+
+/// The rectangle of the grid to draw, starting at `top_left`.
+/// Any part lying past the grid edges is clipped.
+#[derive(Debug, Copy, Clone, Eq, PartialEq)]
+pub struct Viewport {
+    pub top_left: GridPoint2D,
+    pub n_rows: usize,
+    pub n_cols: usize,
+}
+
+impl Viewport {
+    #[allow(dead_code)]
+    pub fn new(top_left: GridPoint2D, n_rows: usize, n_cols: usize) -> Self {
+        Self {
+            top_left,
+            n_rows,
+            n_cols,
+        }
+    }
+
+    /// A viewport of the given size, centred on `center` where `grid` allows,
+    /// and otherwise moved so as to lie wholly within it.
+    /// A viewport larger than the grid starts at its top left.
+    #[allow(dead_code)]
+    pub fn centered<T>(
+        grid: &Grid2D<T>,
+        center: GridPoint2D,
+        n_rows: usize,
+        n_cols: usize,
+    ) -> Self {
+        Self {
+            top_left: GridPoint2D::new(
+                min(
+                    center.row.saturating_sub(n_rows / 2),
+                    grid.n_rows().saturating_sub(n_rows),
+                ),
+                min(
+                    center.col.saturating_sub(n_cols / 2),
+                    grid.n_cols().saturating_sub(n_cols),
+                ),
+            ),
+            n_rows,
+            n_cols,
+        }
+    }
+}
+
+/// Draws a grid to the terminal, converting each cell with `glyph_fn`
+/// and then drawing each overlay layer on top, in the order they were added.
+pub struct Renderer<'a, T> {
+    grid: &'a Grid2D<T>,
+    glyph_fn: Box<dyn Fn(&T) -> Glyph + 'a>,
+    layers: Vec<Layer>,
+    viewport: Option<Viewport>,
+}
+
+impl<'a, T> Renderer<'a, T> {
+    pub fn new(grid: &'a Grid2D<T>, glyph_fn: impl Fn(&T) -> Glyph + 'a) -> Self {
+        Self {
+            grid,
+            glyph_fn: Box::new(glyph_fn),
+            layers: vec![],
+            viewport: None,
+        }
+    }
+
+    pub fn with_layer(mut self, layer: Layer) -> Self {
+        self.layers.push(layer);
+        self
+    }
+
+    #[allow(dead_code)]
+    pub fn with_viewport(mut self, viewport: Viewport) -> Self {
+        self.viewport = Some(viewport);
+        self
+    }
+
+    /// Composes the base grid and overlays within the viewport.
+    fn compose(&self) -> Grid2D<Glyph> {
+        let viewport = self.viewport.unwrap_or(Viewport {
+            top_left: GridPoint2D::new(0, 0),
+            n_rows: self.grid.n_rows(),
+            n_cols: self.grid.n_cols(),
+        });
+
+        let top = min(viewport.top_left.row, self.grid.n_rows());
+        let left = min(viewport.top_left.col, self.grid.n_cols());
+        let n_rows = min(viewport.n_rows, self.grid.n_rows() - top);
+        let n_cols = min(viewport.n_cols, self.grid.n_cols() - left);
+
+        let mut cells = Vec::with_capacity(n_rows * n_cols);
+        for row in top..top + n_rows {
+            cells.extend(
+                self.grid.row_unchecked(row)[left..left + n_cols]
+                    .iter()
+                    .map(&self.glyph_fn),
+            );
+        }
+        let mut composed = Grid2D::from(cells, n_rows, n_cols);
+
+        for layer in &self.layers {
+            for &(point, glyph) in &layer.cells {
+                if point.row < top || point.col < left {
+                    continue;
+                }
+
+                let local = GridPoint2D::new(point.row - top, point.col - left);
+                if let Some(cell) = composed.get_mut(local) {
+                    *cell = glyph;
+                }
+            }
+        }
+
+        composed
+    }
+
+    /// Writes the grid, with ANSI colour codes if `color` is set.
+    pub fn write_to(&self, out: &mut impl Write, color: bool) -> io::Result<()> {
+        let composed = self.compose();
+
+        for row in 0..composed.n_rows() {
+            let mut current_style: Option<Glyph> = None;
+            for &glyph in composed.row_unchecked(row) {
+                if color && !current_style.is_some_and(|style| style.same_style(glyph)) {
+                    write!(out, "\x1b[0")?;
+                    if let Some(fg) = glyph.fg {
+                        write!(out, ";")?;
+                        fg.write_code(out, true)?;
+                    }
+                    if let Some(bg) = glyph.bg {
+                        write!(out, ";")?;
+                        bg.write_code(out, false)?;
+                    }
+                    write!(out, "m")?;
+                    current_style = Some(glyph);
+                }
+
+                write!(out, "{}", glyph.ch)?;
+            }
+
+            if color {
+                write!(out, "\x1b[0m")?;
+            }
+            writeln!(out)?;
+        }
+
+        Ok(())
+    }
+
+    /// Prints to stdout, only using colour if stdout is a terminal.
+    pub fn print(&self) -> io::Result<()> {
+        let stdout = io::stdout();
+        let color = stdout.is_terminal();
+        let mut lock = stdout.lock();
+        self.write_to(&mut lock, color)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn render(renderer: &Renderer<u8>, color: bool) -> String {
+        let mut out = vec![];
+        renderer.write_to(&mut out, color).unwrap();
+        String::from_utf8(out).unwrap()
+    }
+
+    #[test]
+    fn test_plain_with_layers_and_viewport() {
+        let grid = Grid2D::from((0..12).collect(), 3, 4);
+        let renderer = Renderer::new(&grid, |&value| {
+            Glyph::plain(char::from_digit(value as u32 % 10, 10).unwrap())
+        })
+        .with_layer(Layer::points([GridPoint2D::new(0, 0)], Glyph::plain('#')))
+        .with_layer(Layer::label(GridPoint2D::new(2, 2), "ab", Color::Red));
+
+        assert_eq!("#123\n4567\n89ab\n", render(&renderer, false));
+
+        let cropped = renderer.with_viewport(Viewport::new(GridPoint2D::new(1, 1), 5, 2));
+        assert_eq!("56\n9a\n", render(&cropped, false));
+    }
+
+    #[test]
+    fn test_centered_viewport() {
+        let grid = Grid2D::from(vec![0; 30], 5, 6);
+        let centered = |row, col| Viewport::centered(&grid, GridPoint2D::new(row, col), 3, 2);
+
+        assert_eq!(GridPoint2D::new(1, 2), centered(2, 3).top_left);
+        assert_eq!(GridPoint2D::new(0, 0), centered(0, 0).top_left);
+        assert_eq!(GridPoint2D::new(2, 4), centered(4, 5).top_left);
+        assert_eq!(GridPoint2D::new(2, 0), centered(9, 0).top_left);
+
+        let oversized = Viewport::centered(&grid, GridPoint2D::new(4, 5), 7, 6);
+        assert_eq!(GridPoint2D::new(0, 0), oversized.top_left);
+    }
+
+    #[test]
+    fn test_color_codes() {
+        let grid = Grid2D::from(vec![0, 0, 1], 1, 3);
+        let renderer = Renderer::new(&grid, |&value| {
+            if value == 1 {
+                Glyph::plain('x').fg(Color::Green)
+            } else {
+                Glyph::plain('.')
+            }
+        });
+
+        assert_eq!("\x1b[0m..\x1b[0;32mx\x1b[0m\n", render(&renderer, true));
+    }
+}
@@ -0,0 +1,389 @@
+use num::Zero;
+use std::cmp::Ordering;
+use std::collections::hash_map::Entry;
+use std::collections::{BinaryHeap, HashMap, VecDeque};
+use std::hash::Hash;
+use std::ops::Add;
+
+/// Outcome of a search from one or more start states.
+///
+/// `distances` holds the best known cost for every state reached,
+/// and `predecessors` the state each one was reached from (start states have none).
+/// If the search stopped at a goal, `goal` holds the state that satisfied the goal predicate.
+#[derive(Debug, Clone)]
+pub struct SearchResult<S, C> {
+    pub distances: HashMap<S, C>,
+    pub predecessors: HashMap<S, S>,
+    pub goal: Option<S>,
+}
+
+#[allow(dead_code)]
+impl<S, C> SearchResult<S, C>
+where
+    S: Clone + Eq + Hash,
+    C: Copy,
+{
+    fn new() -> Self {
+        Self {
+            distances: HashMap::new(),
+            predecessors: HashMap::new(),
+            goal: None,
+        }
+    }
+
+    pub fn goal_cost(&self) -> Option<C> {
+        self.goal
+            .as_ref()
+            .and_then(|goal| self.distances.get(goal).copied())
+    }
+
+    /// Returns the states from a start state up to and including `state`,
+    /// or `None` if `state` was never reached.
+    pub fn path_to(&self, state: &S) -> Option<Vec<S>> {
+        if !self.distances.contains_key(state) {
+            return None;
+        }
+
+        let mut path = vec![state.clone()];
+        let mut current = state;
+        while let Some(previous) = self.predecessors.get(current) {
+            path.push(previous.clone());
+            current = previous;
+        }
+
+        path.reverse();
+        Some(path)
+    }
+}
+
+/// Breadth-first search, where every transition costs 1.
+/// Stops as soon as a state satisfying `is_goal` is found.
+/// Pass `|_| false` to explore everything reachable.
+#[allow(dead_code)]
+pub fn bfs<S, I>(
+    starts: impl IntoIterator<Item = S>,
+    mut successors: impl FnMut(&S) -> I,
+    mut is_goal: impl FnMut(&S) -> bool,
+) -> SearchResult<S, usize>
+where
+    S: Clone + Eq + Hash,
+    I: IntoIterator<Item = S>,
+{
+    let mut result = SearchResult::new();
+    let mut queue = VecDeque::new();
+
+    for start in starts {
+        if let Entry::Vacant(entry) = result.distances.entry(start.clone()) {
+            entry.insert(0);
+            queue.push_back((start, 0));
+        }
+    }
+
+    while let Some((state, steps)) = queue.pop_front() {
+        if is_goal(&state) {
+            result.goal = Some(state);
+            break;
+        }
+
+        for next in successors(&state) {
+            if let Entry::Vacant(entry) = result.distances.entry(next.clone()) {
+                entry.insert(steps + 1);
+                result.predecessors.insert(next.clone(), state.clone());
+                queue.push_back((next, steps + 1));
+            }
+        }
+    }
+
+    result
+}
+
+/// Dijkstra's algorithm. `successors` yields each neighbouring state with the cost to move there.
+/// Stops as soon as a state satisfying `is_goal` is taken off the queue.
+#[allow(dead_code)]
+pub fn dijkstra<S, C, I>(
+    starts: impl IntoIterator<Item = S>,
+    successors: impl FnMut(&S) -> I,
+    is_goal: impl FnMut(&S) -> bool,
+) -> SearchResult<S, C>
+where
+    S: Clone + Eq + Hash,
+    C: Copy + Ord + Zero + Add<Output = C>,
+    I: IntoIterator<Item = (S, C)>,
+{
+    astar(starts, successors, |_| C::zero(), is_goal)
+}
+
+/// A* search. `heuristic` must never overestimate the remaining cost to a goal,
+/// otherwise the goal found may not be the cheapest.
+#[allow(dead_code)]
+pub fn astar<S, C, I>(
+    starts: impl IntoIterator<Item = S>,
+    successors: impl FnMut(&S) -> I,
+    heuristic: impl FnMut(&S) -> C,
+    is_goal: impl FnMut(&S) -> bool,
+) -> SearchResult<S, C>
+where
+    S: Clone + Eq + Hash,
+    C: Copy + Ord + Zero + Add<Output = C>,
+    I: IntoIterator<Item = (S, C)>,
+{
+    best_first(SearchResult::new(), starts, successors, heuristic, is_goal)
+}
+
+/// Outcome of a search over states numbered by an index function.
+/// As `SearchResult`, but stored densely, by each state's number.
+pub struct IndexedSearchResult<S, C, F> {
+    pub distances: Vec<Option<C>>,
+    pub predecessors: Vec<Option<S>>,
+    pub goal: Option<S>,
+    index: F,
+}
+
+impl<S, C, F> IndexedSearchResult<S, C, F>
+where
+    S: Clone,
+    C: Copy,
+    F: Fn(&S) -> usize,
+{
+    pub fn goal_cost(&self) -> Option<C> {
+        self.goal
+            .as_ref()
+            .and_then(|goal| self.distances[(self.index)(goal)])
+    }
+
+    /// Returns the states from a start state up to and including `state`,
+    /// or `None` if `state` was never reached.
+    pub fn path_to(&self, state: &S) -> Option<Vec<S>> {
+        self.distances.get((self.index)(state)).copied().flatten()?;
+
+        let mut path = vec![state.clone()];
+        while let Some(previous) = &self.predecessors[(self.index)(path.last().unwrap())] {
+            path.push(previous.clone());
+        }
+
+        path.reverse();
+        Some(path)
+    }
+}
+
+/// Dijkstra's algorithm over states that `index` numbers within `0..n_states`,
+/// for when states are cheap to number and a `HashMap` per state would dominate the run time.
+/// Otherwise as `dijkstra`.
+pub fn dijkstra_indexed<S, C, I, F>(
+    n_states: usize,
+    index: F,
+    starts: impl IntoIterator<Item = S>,
+    successors: impl FnMut(&S) -> I,
+    is_goal: impl FnMut(&S) -> bool,
+) -> IndexedSearchResult<S, C, F>
+where
+    S: Clone,
+    C: Copy + Ord + Zero + Add<Output = C>,
+    I: IntoIterator<Item = (S, C)>,
+    F: Fn(&S) -> usize,
+{
+    let result = IndexedSearchResult {
+        distances: vec![None; n_states],
+        predecessors: vec![None; n_states],
+        goal: None,
+        index,
+    };
+    best_first(result, starts, successors, |_| C::zero(), is_goal)
+}
+
+/// Where a search keeps the best known cost and predecessor of each state.
+trait Bookkeeping<S, C> {
+    fn best_cost(&self, state: &S) -> Option<C>;
+
+    fn record(&mut self, state: &S, cost: C, predecessor: Option<&S>);
+
+    fn set_goal(&mut self, goal: S);
+}
+
+impl<S, C> Bookkeeping<S, C> for SearchResult<S, C>
+where
+    S: Clone + Eq + Hash,
+    C: Copy,
+{
+    fn best_cost(&self, state: &S) -> Option<C> {
+        self.distances.get(state).copied()
+    }
+
+    fn record(&mut self, state: &S, cost: C, predecessor: Option<&S>) {
+        self.distances.insert(state.clone(), cost);
+        if let Some(predecessor) = predecessor {
+            self.predecessors.insert(state.clone(), predecessor.clone());
+        }
+    }
+
+    fn set_goal(&mut self, goal: S) {
+        self.goal = Some(goal);
+    }
+}
+
+impl<S, C, F> Bookkeeping<S, C> for IndexedSearchResult<S, C, F>
+where
+    S: Clone,
+    C: Copy,
+    F: Fn(&S) -> usize,
+{
+    fn best_cost(&self, state: &S) -> Option<C> {
+        self.distances[(self.index)(state)]
+    }
+
+    fn record(&mut self, state: &S, cost: C, predecessor: Option<&S>) {
+        let index = (self.index)(state);
+        self.distances[index] = Some(cost);
+        self.predecessors[index] = predecessor.cloned();
+    }
+
+    fn set_goal(&mut self, goal: S) {
+        self.goal = Some(goal);
+    }
+}
+
+/// The loop shared by `astar`, `dijkstra` and `dijkstra_indexed`,
+/// which differ only in how they keep their bookkeeping.
+fn best_first<S, C, I, B>(
+    mut result: B,
+    starts: impl IntoIterator<Item = S>,
+    mut successors: impl FnMut(&S) -> I,
+    mut heuristic: impl FnMut(&S) -> C,
+    mut is_goal: impl FnMut(&S) -> bool,
+) -> B
+where
+    C: Copy + Ord + Zero + Add<Output = C>,
+    I: IntoIterator<Item = (S, C)>,
+    B: Bookkeeping<S, C>,
+{
+    let mut queue = BinaryHeap::new();
+
+    for start in starts {
+        if result.best_cost(&start).is_none() {
+            result.record(&start, C::zero(), None);
+            let priority = heuristic(&start);
+            queue.push(QueueEntry {
+                priority,
+                cost: C::zero(),
+                state: start,
+            });
+        }
+    }
+
+    while let Some(QueueEntry { cost, state, .. }) = queue.pop() {
+        // Skip stale entries, superseded by a cheaper route found after they were queued.
+        if result.best_cost(&state).is_some_and(|best| best < cost) {
+            continue;
+        }
+
+        if is_goal(&state) {
+            result.set_goal(state);
+            break;
+        }
+
+        for (next, step_cost) in successors(&state) {
+            let next_cost = cost + step_cost;
+            if result.best_cost(&next).is_none_or(|best| next_cost < best) {
+                result.record(&next, next_cost, Some(&state));
+                let priority = next_cost + heuristic(&next);
+                queue.push(QueueEntry {
+                    priority,
+                    cost: next_cost,
+                    state: next,
+                });
+            }
+        }
+    }
+
+    result
+}
+
+/// Ordered in reverse by priority, so that `BinaryHeap` pops the cheapest entry first.
+struct QueueEntry<S, C> {
+    priority: C,
+    cost: C,
+    state: S,
+}
+
+impl<S, C: Ord> PartialEq for QueueEntry<S, C> {
+    fn eq(&self, other: &Self) -> bool {
+        self.priority == other.priority
+    }
+}
+
+impl<S, C: Ord> Eq for QueueEntry<S, C> {}
+
+impl<S, C: Ord> PartialOrd for QueueEntry<S, C> {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl<S, C: Ord> Ord for QueueEntry<S, C> {
+    fn cmp(&self, other: &Self) -> Ordering {
+        other.priority.cmp(&self.priority)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // 0 -> 1 (cost 4), 0 -> 2 (cost 1), 2 -> 1 (cost 2), 1 -> 3 (cost 1), 2 -> 3 (cost 5)
+    fn edges(node: &u32) -> Vec<(u32, u64)> {
+        match node {
+            0 => vec![(1, 4), (2, 1)],
+            1 => vec![(3, 1)],
+            2 => vec![(1, 2), (3, 5)],
+            _ => vec![],
+        }
+    }
+
+    #[test]
+    fn test_dijkstra_path() {
+        let result = dijkstra([0], edges, |&node| node == 3);
+
+        assert_eq!(Some(3), result.goal);
+        assert_eq!(Some(4), result.goal_cost());
+        assert_eq!(Some(vec![0, 2, 1, 3]), result.path_to(&3));
+    }
+
+    #[test]
+    fn test_astar_matches_dijkstra() {
+        let heuristic = |node: &u32| match node {
+            0 => 3,
+            1 => 1,
+            2 => 2,
+            _ => 0,
+        };
+        let result = astar([0], edges, heuristic, |&node| node == 3);
+
+        assert_eq!(Some(4), result.goal_cost());
+        assert_eq!(Some(vec![0, 2, 1, 3]), result.path_to(&3));
+    }
+
+    #[test]
+    fn test_bfs_exhaustive() {
+        let result = bfs(
+            [0],
+            |&node: &u32| edges(&node).into_iter().map(|(next, _)| next),
+            |_| false,
+        );
+
+        assert_eq!(None, result.goal);
+        assert_eq!(Some(&1), result.distances.get(&1));
+        assert_eq!(Some(&2), result.distances.get(&3));
+        assert_eq!(Some(vec![0, 1, 3]), result.path_to(&3));
+        assert_eq!(None, result.path_to(&7));
+    }
+
+    #[test]
+    fn test_dijkstra_indexed_matches_dijkstra() {
+        let result = dijkstra_indexed(5, |&node| node as usize, [0], edges, |&node| node == 3);
+
+        assert_eq!(Some(3), result.goal);
+        assert_eq!(Some(4), result.goal_cost());
+        assert_eq!(Some(vec![0, 2, 1, 3]), result.path_to(&3));
+        assert_eq!(None, result.path_to(&4));
+    }
+}